@@ -0,0 +1,248 @@
+//! Validating a command tree that plugins contribute to at runtime.
+//!
+//! Plugin subtrees often only meet the host's tree in the final binary, so
+//! they can't be checked on their own. Collecting them first and checking
+//! the composed tree once lets each violation be traced back to the plugin
+//! that has to fix it.
+
+use std::collections::BTreeMap;
+
+use crate::{Report, SortConfig, Violation};
+
+/// A command tree assembled at runtime from subtrees contributed by plugins.
+///
+/// Plugins register their commands as the application starts up, and the
+/// composed tree is validated once at the end. Violations are attributed to
+/// the plugin whose subtree they were found in; everything else, including
+/// the order plugins' commands end up in among the host's own, belongs to
+/// the host.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::{Assembly, SortConfig};
+///
+/// let mut app = Assembly::new(Command::new("mycli").subcommand(Command::new("plugins")));
+/// app.register("backup", &["plugins"], Command::new("backup")).unwrap();
+/// app.register(
+///     "sync",
+///     &["plugins"],
+///     Command::new("sync")
+///         .subcommand(Command::new("push"))
+///         .subcommand(Command::new("pull")),
+/// )
+/// .unwrap();
+///
+/// let reports = app.validate_assembled(&SortConfig::default());
+/// assert!(reports["mycli"].is_ok());
+/// assert_eq!(reports["sync"].errors.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Assembly {
+    root: clap::Command,
+    plugins: Vec<(String, Vec<String>)>,
+}
+
+impl Assembly {
+    /// Starts from the host application's own command tree.
+    pub fn new(root: clap::Command) -> Self {
+        Self {
+            root,
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Adds `cmd`, owned by `plugin`, below the subcommand at `parent`.
+    ///
+    /// `parent` names subcommands starting below the root, like in
+    /// [`assert_sorted_at`](crate::assert_sorted_at); an empty path adds
+    /// `cmd` to the root itself.
+    pub fn register(
+        &mut self,
+        plugin: &str,
+        parent: &[&str],
+        cmd: clap::Command,
+    ) -> Result<(), String> {
+        let mut current = &self.root;
+        let mut path = vec![current.get_name().to_string()];
+        for name in parent {
+            current = current.find_subcommand(name).ok_or_else(|| {
+                format!("Subcommand '{}' not found in '{}'", name, path.join(" "))
+            })?;
+            path.push(current.get_name().to_string());
+        }
+        path.push(cmd.get_name().to_string());
+
+        // Aliases in `parent` were resolved above, so attach by name
+        let root = std::mem::replace(&mut self.root, clap::Command::new(""));
+        self.root = attach(root, &path[1..path.len() - 1], cmd);
+        self.plugins.push((plugin.to_string(), path));
+        Ok(())
+    }
+
+    /// Returns the composed command tree.
+    pub fn command(&self) -> &clap::Command {
+        &self.root
+    }
+
+    /// Returns the composed command tree, for the application to run.
+    pub fn into_command(self) -> clap::Command {
+        self.root
+    }
+
+    /// Checks the composed tree, returning one report per plugin plus one
+    /// for the host, keyed by the root command's name.
+    ///
+    /// Every registered plugin has a report, even when it is empty.
+    pub fn validate_assembled(&self, config: &SortConfig) -> BTreeMap<String, Report> {
+        let report = crate::check_all(&self.root, config);
+
+        let mut reports: BTreeMap<String, Report> = BTreeMap::new();
+        let host = self.root.get_name().to_string();
+        reports.entry(host.clone()).or_default().omitted = report.omitted;
+        for (plugin, _) in &self.plugins {
+            reports.entry(plugin.clone()).or_default();
+        }
+        for owned in reports.values_mut() {
+            owned.config_files = report.config_files.clone();
+        }
+
+        for violation in report.errors {
+            let owner = self.owner(&violation).unwrap_or(&host);
+            reports.get_mut(owner).unwrap().errors.push(violation);
+        }
+        for violation in report.warnings {
+            let owner = self.owner(&violation).unwrap_or(&host);
+            reports.get_mut(owner).unwrap().warnings.push(violation);
+        }
+        reports
+    }
+
+    /// Finds the plugin that registered the deepest subtree containing the
+    /// command `violation` was found in.
+    fn owner(&self, violation: &Violation) -> Option<&String> {
+        self.plugins
+            .iter()
+            .filter(|(_, path)| violation.path.starts_with(path))
+            .max_by_key(|(_, path)| path.len())
+            .map(|(plugin, _)| plugin)
+    }
+}
+
+/// Adds `sub` below the subcommand of `cmd` at `parent`.
+///
+/// Unlike `mut_subcommand`, which moves the changed subcommand to the end,
+/// this keeps every subcommand where it was declared.
+fn attach(cmd: clap::Command, parent: &[String], sub: clap::Command) -> clap::Command {
+    let Some((name, rest)) = parent.split_first() else {
+        return cmd.subcommand(sub);
+    };
+    let mut sub = Some(sub);
+    cmd.mut_subcommands(|c| match sub.take() {
+        Some(sub) if c.get_name() == name => attach(c, rest, sub),
+        other => {
+            sub = other;
+            c
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Command;
+
+    #[test]
+    fn test_register_nests_below_parent() {
+        let mut app = Assembly::new(
+            Command::new("mycli")
+                .subcommand(Command::new("plugins").subcommand(Command::new("net"))),
+        );
+        app.register("dns", &["plugins", "net"], Command::new("dns"))
+            .unwrap();
+
+        let net = app
+            .command()
+            .find_subcommand("plugins")
+            .and_then(|p| p.find_subcommand("net"))
+            .unwrap();
+        assert!(net.find_subcommand("dns").is_some());
+
+        let err = app
+            .register("x", &["missing"], Command::new("x"))
+            .unwrap_err();
+        assert_eq!(err, "Subcommand 'missing' not found in 'mycli'");
+    }
+
+    #[test]
+    fn test_register_keeps_host_order() {
+        let mut app = Assembly::new(
+            Command::new("mycli")
+                .subcommand(Command::new("plugins"))
+                .subcommand(Command::new("zoo")),
+        );
+        app.register("backup", &["plugins"], Command::new("backup"))
+            .unwrap();
+
+        let names: Vec<&str> = app
+            .command()
+            .get_subcommands()
+            .map(|s| s.get_name())
+            .collect();
+        assert_eq!(names, ["plugins", "zoo"]);
+        assert!(app.validate_assembled(&SortConfig::default())["mycli"].is_ok());
+    }
+
+    #[test]
+    fn test_register_below_alias() {
+        let mut app =
+            Assembly::new(Command::new("mycli").subcommand(Command::new("plugins").alias("pl")));
+        app.register("backup", &["pl"], Command::new("backup"))
+            .unwrap();
+
+        let plugins = app.command().find_subcommand("plugins").unwrap();
+        assert!(plugins.find_subcommand("backup").is_some());
+
+        let reports = app.validate_assembled(&SortConfig::default());
+        assert!(reports.contains_key("backup"));
+    }
+
+    #[test]
+    fn test_violations_are_attributed_to_owner() {
+        let mut app = Assembly::new(
+            Command::new("mycli")
+                .subcommand(Command::new("list"))
+                .subcommand(Command::new("add")),
+        );
+        app.register(
+            "extras",
+            &[],
+            Command::new("zoo")
+                .subcommand(Command::new("b"))
+                .subcommand(Command::new("a")),
+        )
+        .unwrap();
+        app.register(
+            "nested",
+            &["zoo"],
+            Command::new("c")
+                .subcommand(Command::new("y"))
+                .subcommand(Command::new("x")),
+        )
+        .unwrap();
+
+        let reports = app.validate_assembled(&SortConfig::default());
+
+        let paths = |owner: &str| -> Vec<String> {
+            reports[owner]
+                .errors
+                .iter()
+                .map(|v| v.path.join(" "))
+                .collect()
+        };
+        assert_eq!(paths("mycli"), ["mycli"]);
+        assert_eq!(paths("extras"), ["mycli zoo"]);
+        assert_eq!(paths("nested"), ["mycli zoo c"]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Memoized results for command subtrees.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::{SortConfig, Violation};
+
+/// Remembers the violations found in each command subtree, so repeated
+/// checks of the same (or a slightly changed) `Command` skip the subtrees
+/// that haven't changed.
+///
+/// Subtrees are fingerprinted from their full `Debug` output, so anything
+/// that changes a command invalidates its entry and those of its parents.
+/// Changing the configuration clears the cache.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::{CheckCache, SortConfig};
+///
+/// let mut cache = CheckCache::new();
+/// let config = SortConfig::default();
+///
+/// let cmd = Command::new("mycli").subcommand(Command::new("add"));
+/// assert!(clap_sort::check_all_cached(&cmd, &config, &mut cache).is_ok());
+///
+/// // Only the root is checked again; `add` comes from the cache
+/// let cmd = cmd.subcommand(Command::new("list"));
+/// assert!(clap_sort::check_all_cached(&cmd, &config, &mut cache).is_ok());
+/// ```
+#[derive(Debug, Default)]
+pub struct CheckCache {
+    config: Option<u64>,
+    subtrees: HashMap<u64, Vec<Violation>>,
+    hits: usize,
+}
+
+impl CheckCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of subtrees remembered.
+    pub fn len(&self) -> usize {
+        self.subtrees.len()
+    }
+
+    /// Returns true if no subtree is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.subtrees.is_empty()
+    }
+
+    /// Forgets every subtree.
+    pub fn clear(&mut self) {
+        self.subtrees.clear();
+    }
+
+    /// Clears the cache if it was filled using a different configuration.
+    pub(crate) fn use_config(&mut self, config: &SortConfig) {
+        let fingerprint = fingerprint(|hasher| {
+            hash_debug(hasher, config);
+        });
+        if self.config != Some(fingerprint) {
+            self.subtrees.clear();
+            self.config = Some(fingerprint);
+        }
+    }
+
+    /// Fingerprints the subtree rooted at `cmd`, checked below `parent_path`
+    /// inside a multicall applet or not.
+    pub(crate) fn key(&self, parent_path: &[&str], in_applet: bool, cmd: &clap::Command) -> u64 {
+        fingerprint(|hasher| {
+            parent_path.hash(hasher);
+            in_applet.hash(hasher);
+            hash_debug(hasher, cmd);
+        })
+    }
+
+    pub(crate) fn get(&mut self, key: u64) -> Option<&[Violation]> {
+        let found = self.subtrees.get(&key)?;
+        self.hits += 1;
+        Some(found)
+    }
+
+    pub(crate) fn insert(&mut self, key: u64, violations: &[Violation]) {
+        self.subtrees.insert(key, violations.to_vec());
+    }
+}
+
+fn fingerprint(write: impl FnOnce(&mut DefaultHasher)) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    write(&mut hasher);
+    hasher.finish()
+}
+
+/// Feeds the `Debug` output of `value` to `hasher` without allocating it.
+fn hash_debug(hasher: &mut DefaultHasher, value: &impl fmt::Debug) {
+    struct HashWriter<'a>(&'a mut DefaultHasher);
+
+    impl fmt::Write for HashWriter<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    fmt::write(&mut HashWriter(hasher), format_args!("{:?}", value)).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Command;
+
+    fn tree(leaf: &'static str) -> Command {
+        Command::new("mycli")
+            .subcommand(
+                Command::new("add")
+                    .subcommand(Command::new("b"))
+                    .subcommand(Command::new("a")),
+            )
+            .subcommand(Command::new("list").subcommand(Command::new(leaf)))
+    }
+
+    #[test]
+    fn test_unchanged_subtrees_come_from_cache() {
+        let config = SortConfig::default();
+        let mut cache = CheckCache::new();
+
+        let first = crate::check_all_cached(&tree("x"), &config, &mut cache);
+        assert_eq!(cache.hits, 0);
+        assert_eq!(first, crate::check_all(&tree("x"), &config));
+
+        // `add` is reused; `list` and the root are checked again
+        let second = crate::check_all_cached(&tree("y"), &config, &mut cache);
+        assert_eq!(cache.hits, 1);
+        assert_eq!(second, crate::check_all(&tree("y"), &config));
+
+        let third = crate::check_all_cached(&tree("y"), &config, &mut cache);
+        assert_eq!(cache.hits, 2);
+        assert_eq!(third, second);
+    }
+
+    #[test]
+    fn test_config_change_clears_cache() {
+        let mut cache = CheckCache::new();
+
+        crate::check_all_cached(&tree("x"), &SortConfig::default(), &mut cache);
+        assert!(!cache.is_empty());
+
+        let relaxed = SortConfig::new().subcommands(false);
+        let report = crate::check_all_cached(&tree("x"), &relaxed, &mut cache);
+        assert_eq!(cache.hits, 0);
+        assert!(report.is_ok());
+    }
+}
@@ -0,0 +1,496 @@
+//! Rust source generation for command trees.
+//!
+//! Generated definitions list subcommands and arguments in sorted order, so
+//! they pass the checks as soon as they are pasted in. Only what can be read
+//! back from a `clap::Command` is reproduced; value types default to
+//! `String` because the value parser's output type isn't recoverable.
+
+use std::fmt::Write;
+
+use crate::SortConfig;
+use crate::fix::{grouped_arg_order, subcommand_order};
+use crate::rules::is_builtin_flag;
+
+/// Generates a `#[derive(Subcommand)]` enum named `name` for the
+/// subcommands of `cmd`, followed by one enum per nested command group.
+pub(crate) fn subcommand_enum(cmd: &clap::Command, name: &str, config: &SortConfig) -> String {
+    let mut out = String::new();
+    write_enum(&mut out, cmd, name, "", config, &[cmd.get_name()]);
+    out
+}
+
+fn write_enum(
+    out: &mut String,
+    cmd: &clap::Command,
+    name: &str,
+    prefix: &str,
+    root_config: &SortConfig,
+    path: &[&str],
+) {
+    let depth = path.len();
+    let config = root_config.for_depth(depth - 1);
+    let subcommands: Vec<&clap::Command> = cmd.get_subcommands().collect();
+    let mut nested = Vec::new();
+
+    writeln!(out, "#[derive(Subcommand)]").unwrap();
+    writeln!(out, "pub enum {} {{", name).unwrap();
+
+    for index in subcommand_order(cmd, path, config) {
+        let sub = subcommands[index];
+        let variant = pascal_case(sub.get_name());
+
+        write_docs(out, sub.get_about(), 1);
+        let attrs = command_attrs(sub, &variant);
+        if !attrs.is_empty() {
+            writeln!(out, "    #[command({})]", attrs.join(", ")).unwrap();
+        }
+
+        let has_args = sub.get_arguments().any(|a| !is_builtin_flag(a));
+        if !has_args && !sub.has_subcommands() {
+            writeln!(out, "    {},", variant).unwrap();
+            continue;
+        }
+
+        writeln!(out, "    {} {{", variant).unwrap();
+        write_fields(out, sub, root_config.for_depth(depth), 2);
+        if sub.has_subcommands() {
+            let nested_name = format!("{}{}Commands", prefix, variant);
+            writeln!(out, "        #[command(subcommand)]").unwrap();
+            if sub.is_subcommand_required_set() {
+                writeln!(out, "        command: {},", nested_name).unwrap();
+            } else {
+                writeln!(out, "        command: Option<{}>,", nested_name).unwrap();
+            }
+            nested.push((sub, nested_name, format!("{}{}", prefix, variant)));
+        }
+        writeln!(out, "    }},").unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+
+    for (sub, nested_name, nested_prefix) in nested {
+        writeln!(out).unwrap();
+        write_enum(
+            out,
+            sub,
+            &nested_name,
+            &nested_prefix,
+            root_config,
+            &[path, &[sub.get_name()]].concat(),
+        );
+    }
+}
+
+/// Writes a field for every argument of `cmd`, grouped and sorted.
+fn write_fields(out: &mut String, cmd: &clap::Command, config: &SortConfig, indent: usize) {
+    let pad = "    ".repeat(indent);
+    let args: Vec<&clap::Arg> = cmd.get_arguments().collect();
+
+    for index in grouped_arg_order(cmd, config) {
+        let arg = args[index];
+        if is_builtin_flag(arg) {
+            continue;
+        }
+
+        write_docs(out, arg.get_help(), indent);
+        let attrs = arg_attrs(arg);
+        if !attrs.is_empty() {
+            writeln!(out, "{}#[arg({})]", pad, attrs.join(", ")).unwrap();
+        }
+        writeln!(
+            out,
+            "{}{}: {},",
+            pad,
+            field_name(arg.get_id().as_str()),
+            field_type(arg)
+        )
+        .unwrap();
+    }
+}
+
+/// Generates a builder-API expression that recreates `cmd` and its
+/// subcommands, with arguments grouped and sorted and subcommands sorted.
+pub(crate) fn builder_source(cmd: &clap::Command, config: &SortConfig) -> String {
+    command_expr(cmd, config, &[cmd.get_name()], 0)
+}
+
+/// Writes a `Command::new(..)` chain starting on a line indented `indent` levels.
+fn command_expr(
+    cmd: &clap::Command,
+    root_config: &SortConfig,
+    path: &[&str],
+    indent: usize,
+) -> String {
+    let config = root_config.for_depth(path.len() - 1);
+    let mut calls = Vec::new();
+
+    if let Some(version) = cmd.get_version() {
+        calls.push(format!(".version({:?})", version));
+    }
+    if let Some(about) = cmd.get_about() {
+        calls.push(format!(".about({:?})", about.to_string()));
+    }
+    let visible: Vec<&str> = cmd.get_visible_aliases().collect();
+    for alias in cmd.get_all_aliases() {
+        if visible.contains(&alias) {
+            calls.push(format!(".visible_alias({:?})", alias));
+        } else {
+            calls.push(format!(".alias({:?})", alias));
+        }
+    }
+    if cmd.is_hide_set() {
+        calls.push(".hide(true)".to_string());
+    }
+    if cmd.is_subcommand_required_set() {
+        calls.push(".subcommand_required(true)".to_string());
+    }
+
+    // clap generates help and version flags itself
+    let args: Vec<&clap::Arg> = cmd.get_arguments().collect();
+    for index in grouped_arg_order(cmd, config) {
+        if is_builtin_flag(args[index]) {
+            continue;
+        }
+        calls.push(nested_call(
+            "arg",
+            arg_expr(args[index], indent + 2),
+            indent + 1,
+        ));
+    }
+
+    let subcommands: Vec<&clap::Command> = cmd.get_subcommands().collect();
+    for index in subcommand_order(cmd, path, config) {
+        let sub = subcommands[index];
+        // Multicall applets are each their own root
+        let sub_path = if cmd.is_multicall_set() {
+            vec![sub.get_name()]
+        } else {
+            [path, &[sub.get_name()]].concat()
+        };
+        let sub = command_expr(sub, root_config, &sub_path, indent + 2);
+        calls.push(nested_call("subcommand", sub, indent + 1));
+    }
+
+    chain(format!("Command::new({:?})", cmd.get_name()), calls, indent)
+}
+
+/// Writes an `Arg::new(..)` chain starting on a line indented `indent` levels.
+fn arg_expr(arg: &clap::Arg, indent: usize) -> String {
+    let mut calls = Vec::new();
+
+    if let Some(short) = arg.get_short() {
+        calls.push(format!(".short({:?})", short));
+    }
+    if let Some(long) = arg.get_long() {
+        calls.push(format!(".long({:?})", long));
+    }
+    if let Some(help) = arg.get_help() {
+        calls.push(format!(".help({:?})", help.to_string()));
+    }
+    if let Some([value_name]) = arg.get_value_names() {
+        calls.push(format!(".value_name({:?})", value_name.as_str()));
+    }
+    if arg.is_required_set() {
+        calls.push(".required(true)".to_string());
+    }
+    if let [default] = arg.get_default_values() {
+        calls.push(format!(".default_value({:?})", default.to_string_lossy()));
+    }
+    if !matches!(arg.get_action(), clap::ArgAction::Set) {
+        calls.push(format!(".action(ArgAction::{:?})", arg.get_action()));
+    }
+    if arg.is_hide_set() {
+        calls.push(".hide(true)".to_string());
+    }
+
+    chain(
+        format!("Arg::new({:?})", arg.get_id().as_str()),
+        calls,
+        indent,
+    )
+}
+
+/// Joins a constructor and its method calls, one call per line unless
+/// there is only a single short call.
+fn chain(mut expr: String, calls: Vec<String>, indent: usize) -> String {
+    if let [call] = calls.as_slice()
+        && !call.contains('\n')
+    {
+        expr.push_str(call);
+        return expr;
+    }
+
+    let pad = "    ".repeat(indent + 1);
+    for call in calls {
+        write!(expr, "\n{}{}", pad, call).unwrap();
+    }
+    expr
+}
+
+/// Wraps `inner` in a `.method(..)` call, breaking the line like rustfmt
+/// when `inner` spans several lines.
+fn nested_call(method: &str, inner: String, indent: usize) -> String {
+    if inner.contains('\n') {
+        let pad = "    ".repeat(indent);
+        format!(".{}(\n{}    {},\n{})", method, pad, inner, pad)
+    } else {
+        format!(".{}({})", method, inner)
+    }
+}
+
+/// Attributes needed to reproduce a subcommand's name and aliases.
+fn command_attrs(cmd: &clap::Command, variant: &str) -> Vec<String> {
+    let mut attrs = Vec::new();
+    if kebab_case(variant) != cmd.get_name() {
+        attrs.push(format!("name = {:?}", cmd.get_name()));
+    }
+    let visible: Vec<&str> = cmd.get_visible_aliases().collect();
+    for alias in cmd.get_all_aliases() {
+        let key = if visible.contains(&alias) {
+            "visible_alias"
+        } else {
+            "alias"
+        };
+        attrs.push(format!("{} = {:?}", key, alias));
+    }
+    if cmd.is_hide_set() {
+        attrs.push("hide = true".to_string());
+    }
+    attrs
+}
+
+/// Attributes needed to reproduce an argument's flags and behavior.
+fn arg_attrs(arg: &clap::Arg) -> Vec<String> {
+    let mut attrs = Vec::new();
+    let id = arg.get_id().as_str();
+
+    if let Some(short) = arg.get_short() {
+        if id.starts_with(short) {
+            attrs.push("short".to_string());
+        } else {
+            attrs.push(format!("short = {:?}", short));
+        }
+    }
+    if let Some(long) = arg.get_long() {
+        if long == id.replace('_', "-") {
+            attrs.push("long".to_string());
+        } else {
+            attrs.push(format!("long = {:?}", long));
+        }
+    }
+    if matches!(arg.get_action(), clap::ArgAction::SetFalse) {
+        attrs.push("action = clap::ArgAction::SetFalse".to_string());
+    }
+    if let [default] = arg.get_default_values()
+        && !matches!(
+            arg.get_action(),
+            clap::ArgAction::SetTrue | clap::ArgAction::SetFalse | clap::ArgAction::Count
+        )
+    {
+        attrs.push(format!("default_value = {:?}", default.to_string_lossy()));
+    }
+    if arg.is_hide_set() {
+        attrs.push("hide = true".to_string());
+    }
+    attrs
+}
+
+/// The field type clap's derive infers the argument's behavior from.
+fn field_type(arg: &clap::Arg) -> &'static str {
+    match arg.get_action() {
+        clap::ArgAction::SetTrue | clap::ArgAction::SetFalse => "bool",
+        clap::ArgAction::Count => "u8",
+        clap::ArgAction::Append => "Vec<String>",
+        _ if arg.get_num_args().is_some_and(|n| n.max_values() > 1) => "Vec<String>",
+        _ if arg.is_required_set() || !arg.get_default_values().is_empty() => "String",
+        _ => "Option<String>",
+    }
+}
+
+fn write_docs(out: &mut String, text: Option<&clap::builder::StyledStr>, indent: usize) {
+    if let Some(text) = text {
+        for line in text.to_string().lines() {
+            writeln!(out, "{}/// {}", "    ".repeat(indent), line).unwrap();
+        }
+    }
+}
+
+/// Converts a command name such as `list-all` to a variant name (`ListAll`).
+fn pascal_case(name: &str) -> String {
+    name.split(['-', '_', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Converts a variant name back to the command name clap's derive gives it.
+fn kebab_case(variant: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('-');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// Converts an argument id to a field name, escaping keywords.
+fn field_name(id: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+        "ref", "return", "static", "struct", "trait", "type", "unsafe", "use", "where", "while",
+    ];
+    let name = id.replace('-', "_").to_lowercase();
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    #[test]
+    fn test_case_conversions() {
+        assert_eq!(pascal_case("list-all"), "ListAll");
+        assert_eq!(pascal_case("task_docs"), "TaskDocs");
+        assert_eq!(kebab_case("ListAll"), "list-all");
+        assert_eq!(field_name("dry-run"), "dry_run");
+        assert_eq!(field_name("type"), "r#type");
+    }
+
+    #[test]
+    fn test_subcommand_enum_is_sorted() {
+        let cmd = Command::new("mycli")
+            .subcommand(
+                Command::new("list")
+                    .about("List things")
+                    .visible_alias("ls"),
+            )
+            .subcommand(
+                Command::new("add")
+                    .arg(
+                        Arg::new("verbose")
+                            .short('v')
+                            .long("verbose")
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Arg::new("file").required(true))
+                    .arg(
+                        Arg::new("debug")
+                            .short('d')
+                            .long("debug")
+                            .action(ArgAction::Count),
+                    ),
+            )
+            .subcommand(Command::new("task_docs").hide(true));
+
+        let source = subcommand_enum(&cmd, "Commands", &SortConfig::default());
+
+        assert_eq!(
+            source,
+            "\
+#[derive(Subcommand)]
+pub enum Commands {
+    Add {
+        file: String,
+        #[arg(short, long)]
+        debug: u8,
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// List things
+    #[command(visible_alias = \"ls\")]
+    List,
+    #[command(name = \"task_docs\", hide = true)]
+    TaskDocs,
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_builder_source_is_sorted() {
+        let cmd = Command::new("mycli")
+            .about("My CLI")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("config").long("config").help("Config file"))
+            .arg(Arg::new("debug").short('d'))
+            .subcommand(Command::new("list").visible_alias("ls"))
+            .subcommand(Command::new("add").arg(Arg::new("file").required(true)));
+
+        assert_eq!(
+            builder_source(&cmd, &SortConfig::default()),
+            r#"Command::new("mycli")
+    .about("My CLI")
+    .arg(Arg::new("debug").short('d'))
+    .arg(
+        Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .action(ArgAction::SetTrue),
+    )
+    .arg(
+        Arg::new("config")
+            .long("config")
+            .help("Config file"),
+    )
+    .subcommand(Command::new("add").arg(Arg::new("file").required(true)))
+    .subcommand(Command::new("list").visible_alias("ls"))"#
+        );
+    }
+
+    #[test]
+    fn test_builder_source_skips_generated_flags() {
+        let mut cmd = Command::new("x")
+            .version("1")
+            .arg(Arg::new("debug").short('d'));
+        cmd.build();
+
+        assert_eq!(
+            builder_source(&cmd, &SortConfig::default()),
+            r#"Command::new("x")
+    .version("1")
+    .arg(Arg::new("debug").short('d'))"#
+        );
+    }
+
+    #[test]
+    fn test_nested_subcommands_get_their_own_enum() {
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("config")
+                .subcommand_required(true)
+                .subcommand(Command::new("set"))
+                .subcommand(Command::new("get")),
+        );
+
+        let source = subcommand_enum(&cmd, "Commands", &SortConfig::default());
+
+        assert!(
+            source.contains("    Config {\n        #[command(subcommand)]\n        command: ConfigCommands,\n    },"),
+            "{}",
+            source
+        );
+        assert!(
+            source.contains("pub enum ConfigCommands {\n    Get,\n    Set,\n}"),
+            "{}",
+            source
+        );
+    }
+}
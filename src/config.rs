@@ -0,0 +1,1545 @@
+use crate::{Severity, Violation, ViolationKind};
+
+/// Placement policy for help and version flags.
+///
+/// clap injects `-h/--help` and `-V/--version` when a command is built, so
+/// whether they show up depends on whether the `Command` was built before it
+/// was validated. Flags with a help or version action are never part of the
+/// alphabetical checks; this policy decides what else is enforced for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BuiltinFlags {
+    /// Help and version flags may appear anywhere.
+    #[default]
+    Ignore,
+    /// Help and version flags must come after every other flag.
+    Last,
+}
+
+/// How subcommands are grouped before being compared alphabetically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Plain alphabetical order.
+    #[default]
+    None,
+    /// Group by the text before the first occurrence of the separator, then
+    /// sort alphabetically within each group.
+    ///
+    /// With `GroupBy::Prefix(':')`, `db:migrate` and `db:seed` form a group
+    /// that sorts as `db`, so it comes before `db2`.
+    Prefix(char),
+}
+
+/// Where short flags that are not letters sort relative to letters.
+///
+/// Short flags fall into three classes: symbols (such as `-#` or `-?`),
+/// digits, and letters. Within a class, characters compare case-insensitively
+/// with lowercase before uppercase for the same letter, so `-i` precedes `-I`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonAlphaShorts {
+    /// No classes: characters compare by code point after lowercasing, so
+    /// `-1` and `-?` come before the letters and `-~` after them.
+    #[default]
+    CharOrder,
+    /// Symbols, then digits, then letters.
+    BeforeLetters,
+    /// Letters, then digits, then symbols.
+    AfterLetters,
+}
+
+/// Which short flag an argument with short aliases is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShortKey {
+    /// The primary short, as set with `Arg::short`.
+    #[default]
+    Primary,
+    /// Whichever of the primary short and its aliases sorts first.
+    First,
+    /// The long flag, for flags that have one, so `-o, --output` sorts as
+    /// `output`. Flags with only a short sort as a one-letter name.
+    Long,
+}
+
+/// The direction items are sorted in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// A to Z.
+    #[default]
+    Ascending,
+    /// Z to A.
+    Descending,
+}
+
+impl Direction {
+    /// Orients an ascending `ordering` in this direction.
+    pub(crate) fn apply(self, ordering: std::cmp::Ordering) -> std::cmp::Ordering {
+        match self {
+            Direction::Ascending => ordering,
+            Direction::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// Naming style required of explicit value names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueNameStyle {
+    /// Uppercase words joined by underscores, like `FILE_PATH`.
+    ScreamingSnakeCase,
+    /// Lowercase words joined by hyphens, like `file-path`.
+    KebabCase,
+}
+
+impl ValueNameStyle {
+    /// Returns true if `name` follows this style.
+    pub(crate) fn matches(self, name: &str) -> bool {
+        let (case_ok, separator): (fn(&char) -> bool, char) = match self {
+            ValueNameStyle::ScreamingSnakeCase => (|c| !c.is_lowercase(), '_'),
+            ValueNameStyle::KebabCase => (|c| !c.is_uppercase(), '-'),
+        };
+        !name.is_empty()
+            && name.split(separator).all(|word| {
+                !word.is_empty() && word.chars().all(|c| c.is_alphanumeric() && case_ok(&c))
+            })
+    }
+}
+
+/// How much of the actual and expected order a violation message shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only the items that have to move, each with the neighbors it belongs
+    /// between, like `-o should come after -m and before -r`.
+    Concise,
+    /// Both orders in full for short lists; only the moved items, with some
+    /// context, for long ones.
+    #[default]
+    Normal,
+    /// Both orders in full, however long.
+    Full,
+}
+
+/// Controls which checks are run when validating a command tree.
+///
+/// The default configuration runs the ordering checks of
+/// [`is_sorted`](crate::is_sorted), plus the checks for definitions that
+/// clap can't parse as written. Stricter checks, such as those for aliases
+/// or help output, are opt-in.
+///
+/// # Example
+///
+/// ```rust
+/// use clap_sort::SortConfig;
+///
+/// // Only enforce subcommand ordering
+/// let config = SortConfig::new().short_flags(false).long_flags(false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SortConfig {
+    pub(crate) subcommands: bool,
+    pub(crate) short_flags: bool,
+    pub(crate) long_flags: bool,
+    pub(crate) group_order: bool,
+    pub(crate) external_subcommands: bool,
+    pub(crate) multicall_applets: bool,
+    pub(crate) alias_collisions: bool,
+    pub(crate) alias_order: bool,
+    pub(crate) builtin_shadowing: bool,
+    pub(crate) reserved_shorts: Vec<(char, String)>,
+    pub(crate) variadic_positionals: bool,
+    pub(crate) positional_order: bool,
+    pub(crate) value_name_style: Option<ValueNameStyle>,
+    pub(crate) possible_values: bool,
+    pub(crate) kebab_case_subcommands: bool,
+    pub(crate) max_subcommand_len: Option<usize>,
+    pub(crate) allowed_subcommand_names: Vec<Vec<String>>,
+    pub(crate) allowed_verbs: Vec<String>,
+    #[cfg(feature = "env")]
+    pub(crate) env_pattern: Option<String>,
+    pub(crate) rendered_help: bool,
+    pub(crate) display_order_consistency: bool,
+    pub(crate) case_insensitive: bool,
+    pub(crate) ignore_separators: bool,
+    pub(crate) display_names: bool,
+    pub(crate) subcommand_direction: Direction,
+    pub(crate) short_flag_direction: Direction,
+    pub(crate) long_flag_direction: Direction,
+    pub(crate) builtin_flags: BuiltinFlags,
+    pub(crate) non_alpha_shorts: NonAlphaShorts,
+    pub(crate) short_key: ShortKey,
+    pub(crate) group_by: GroupBy,
+    pub(crate) subcommand_comparator: Option<fn(&str, &str) -> std::cmp::Ordering>,
+    pub(crate) long_flag_comparator: Option<fn(&str, &str) -> std::cmp::Ordering>,
+    pub(crate) categories: Vec<(String, Vec<String>)>,
+    pub(crate) ignored_subcommands: Vec<String>,
+    pub(crate) ignored_args: Vec<String>,
+    pub(crate) ignored_paths: Vec<Vec<String>>,
+    pub(crate) skip_hidden: bool,
+    pub(crate) pinned_first: Vec<String>,
+    pub(crate) pinned_last: Vec<String>,
+    pub(crate) explicit_orders: Vec<(Vec<String>, Vec<String>)>,
+    pub(crate) severities: Vec<(ViolationKind, Severity)>,
+    pub(crate) disabled_rules: Vec<ViolationKind>,
+    pub(crate) max_violations: Option<usize>,
+    pub(crate) config_files: Vec<std::path::PathBuf>,
+    pub(crate) full_listing: bool,
+    pub(crate) verbosity: Verbosity,
+    pub(crate) hidden_last: bool,
+    pub(crate) contiguous_headings: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) depth_overrides: Vec<(usize, SortConfig)>,
+}
+
+impl Default for SortConfig {
+    fn default() -> Self {
+        Self {
+            subcommands: true,
+            short_flags: true,
+            long_flags: true,
+            group_order: false,
+            external_subcommands: false,
+            multicall_applets: true,
+            alias_collisions: false,
+            alias_order: false,
+            builtin_shadowing: false,
+            reserved_shorts: Vec::new(),
+            variadic_positionals: true,
+            positional_order: false,
+            value_name_style: None,
+            possible_values: false,
+            kebab_case_subcommands: false,
+            max_subcommand_len: None,
+            allowed_subcommand_names: Vec::new(),
+            allowed_verbs: Vec::new(),
+            #[cfg(feature = "env")]
+            env_pattern: None,
+            rendered_help: false,
+            display_order_consistency: false,
+            case_insensitive: false,
+            ignore_separators: false,
+            display_names: false,
+            subcommand_direction: Direction::default(),
+            short_flag_direction: Direction::default(),
+            long_flag_direction: Direction::default(),
+            builtin_flags: BuiltinFlags::default(),
+            non_alpha_shorts: NonAlphaShorts::default(),
+            short_key: ShortKey::default(),
+            group_by: GroupBy::default(),
+            subcommand_comparator: None,
+            long_flag_comparator: None,
+            categories: Vec::new(),
+            ignored_subcommands: Vec::new(),
+            ignored_args: Vec::new(),
+            ignored_paths: Vec::new(),
+            skip_hidden: false,
+            pinned_first: Vec::new(),
+            pinned_last: Vec::new(),
+            explicit_orders: Vec::new(),
+            severities: Vec::new(),
+            disabled_rules: Vec::new(),
+            max_violations: None,
+            config_files: Vec::new(),
+            full_listing: false,
+            verbosity: Verbosity::default(),
+            hidden_last: false,
+            contiguous_headings: false,
+            max_depth: None,
+            depth_overrides: Vec::new(),
+        }
+    }
+}
+
+impl SortConfig {
+    /// Arguments that [`with_conventions`](Self::with_conventions) leaves out
+    /// of the checks, matched by id or long flag.
+    pub const CONVENTIONAL_ARGS: &'static [&'static str] = &["color", "quiet", "verbose"];
+
+    /// Subcommands that [`with_conventions`](Self::with_conventions) pins last.
+    pub const CONVENTIONAL_SUBCOMMANDS: &'static [&'static str] = &["help"];
+
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a configuration that follows common CLI conventions.
+    ///
+    /// This pins [`CONVENTIONAL_SUBCOMMANDS`](Self::CONVENTIONAL_SUBCOMMANDS)
+    /// last (clap appends `help` when the command is built) and ignores
+    /// [`CONVENTIONAL_ARGS`](Self::CONVENTIONAL_ARGS), which are usually
+    /// placed by convention rather than alphabetically. Help and version
+    /// flags are already ignored by default. To adjust the set, build the
+    /// configuration from [`new`](Self::new) using these constants.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// # let cmd = clap::Command::new("mycli");
+    /// assert!(clap_sort::is_sorted_with_config(&cmd, &SortConfig::with_conventions()).is_ok());
+    /// ```
+    pub fn with_conventions() -> Self {
+        Self::new()
+            .pin_last(Self::CONVENTIONAL_SUBCOMMANDS)
+            .ignore_args(Self::CONVENTIONAL_ARGS)
+    }
+
+    /// Enables or disables the alphabetical subcommand check.
+    pub fn subcommands(mut self, enabled: bool) -> Self {
+        self.subcommands = enabled;
+        self
+    }
+
+    /// Enables or disables the check that flags with short options are sorted.
+    pub fn short_flags(mut self, enabled: bool) -> Self {
+        self.short_flags = enabled;
+        self
+    }
+
+    /// Enables or disables the check that long-only flags are sorted.
+    pub fn long_flags(mut self, enabled: bool) -> Self {
+        self.long_flags = enabled;
+        self
+    }
+
+    /// Also checks that arguments are grouped: positionals first, then
+    /// flags with short options, then long-only flags.
+    ///
+    /// Off by default, since flattened structs legitimately interleave the
+    /// groups.
+    pub fn group_order(mut self, enabled: bool) -> Self {
+        self.group_order = enabled;
+        self
+    }
+
+    /// Enables or disables the check that commands allowing external
+    /// subcommands do not also declare optional or variadic positional
+    /// arguments. Off by default.
+    ///
+    /// clap hands a word to a positional argument before treating it as an
+    /// external subcommand, so an omitted optional positional takes the
+    /// subcommand's name, and a variadic one takes it and everything after.
+    pub fn external_subcommands(mut self, enabled: bool) -> Self {
+        self.external_subcommands = enabled;
+        self
+    }
+
+    /// Enables or disables the check that applets of a
+    /// [multicall](clap::Command::multicall) command are sorted.
+    ///
+    /// Each applet is always validated as its own root.
+    pub fn multicall_applets(mut self, enabled: bool) -> Self {
+        self.multicall_applets = enabled;
+        self
+    }
+
+    /// Enables or disables the check that no subcommand name or alias,
+    /// visible or hidden, is shared by two subcommands of the same command.
+    /// Off by default.
+    pub fn alias_collisions(mut self, enabled: bool) -> Self {
+        self.alias_collisions = enabled;
+        self
+    }
+
+    /// Enables or disables the check that each subcommand and flag declares
+    /// its aliases, visible and hidden, in sorted order. Off by default.
+    pub fn alias_order(mut self, enabled: bool) -> Self {
+        self.alias_order = enabled;
+        self
+    }
+
+    /// Enables or disables the check that no flag takes `-h`/`--help`, or
+    /// `-V`/`--version` on a command with a version, while clap still
+    /// generates its own help or version flag. Off by default.
+    pub fn builtin_shadowing(mut self, enabled: bool) -> Self {
+        self.builtin_shadowing = enabled;
+        self
+    }
+
+    /// Requires environment variables set with `Arg::env` to follow `pattern`.
+    ///
+    /// `{ID}` in the pattern is replaced with the argument id in
+    /// SCREAMING_SNAKE_CASE, so with `"MYCLI_{ID}"` the argument `dry-run`
+    /// must read from `MYCLI_DRY_RUN`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new().env_pattern("MYCLI_{ID}");
+    /// ```
+    #[cfg(feature = "env")]
+    pub fn env_pattern(mut self, pattern: &str) -> Self {
+        self.env_pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Enables or disables the check that only the last positional argument
+    /// accepts multiple values. Like clap, an earlier one is allowed when it
+    /// has a `value_terminator`, or when the last positional is required or
+    /// marked `last`.
+    pub fn variadic_positionals(mut self, enabled: bool) -> Self {
+        self.variadic_positionals = enabled;
+        self
+    }
+
+    /// Also checks that required positional arguments come before optional
+    /// ones, and that positionals taking multiple values or marked `last`
+    /// come at the end.
+    ///
+    /// Positionals are otherwise exempt from ordering, since their order
+    /// decides how values are parsed.
+    pub fn positional_order(mut self, enabled: bool) -> Self {
+        self.positional_order = enabled;
+        self
+    }
+
+    /// Requires every explicit value name to follow `style`.
+    ///
+    /// Arguments without a `value_name` are skipped, since clap derives the
+    /// placeholder from the argument id.
+    pub fn value_name_style(mut self, style: ValueNameStyle) -> Self {
+        self.value_name_style = Some(style);
+        self
+    }
+
+    /// Also checks that each argument's possible values, such as the
+    /// variants of a `#[derive(ValueEnum)]` enum, are sorted.
+    pub fn possible_values(mut self, enabled: bool) -> Self {
+        self.possible_values = enabled;
+        self
+    }
+
+    /// Requires subcommand names to be lowercase kebab-case.
+    ///
+    /// The derive API produces such names by default, but builder code
+    /// often doesn't. Use [`allow_subcommand_name`](Self::allow_subcommand_name)
+    /// for names that have to stay as they are.
+    pub fn kebab_case_subcommands(mut self, enabled: bool) -> Self {
+        self.kebab_case_subcommands = enabled;
+        self
+    }
+
+    /// Limits subcommand names to `len` characters.
+    pub fn max_subcommand_len(mut self, len: usize) -> Self {
+        self.max_subcommand_len = Some(len);
+        self
+    }
+
+    /// Requires the first word of leaf subcommand names to be one of `verbs`.
+    ///
+    /// Words are separated by `-`, so `list-users` starts with `list`.
+    /// Subcommands that have subcommands of their own are usually nouns that
+    /// group related commands (`config get`), so only leaves are checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new().allowed_verbs(&["add", "list", "remove", "show"]);
+    /// ```
+    pub fn allowed_verbs(mut self, verbs: &[&str]) -> Self {
+        self.allowed_verbs
+            .extend(verbs.iter().map(|v| v.to_string()));
+        self
+    }
+
+    /// Reserves the short flag `short` for arguments with the id `id`,
+    /// everywhere in the tree.
+    ///
+    /// Keeps conventions like `-v` for verbose, `-q` for quiet and `-f` for
+    /// force consistent across commands.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new()
+    ///     .reserve_short('f', "force")
+    ///     .reserve_short('q', "quiet")
+    ///     .reserve_short('v', "verbose");
+    /// ```
+    pub fn reserve_short(mut self, short: char, id: &str) -> Self {
+        self.reserved_shorts.retain(|(s, _)| *s != short);
+        self.reserved_shorts.push((short, id.to_string()));
+        self
+    }
+
+    /// Exempts the subcommand at `path` from the naming rules.
+    ///
+    /// As with [`assert_sorted_at`](crate::assert_sorted_at), `path` starts
+    /// below the root command and ends with the exempted subcommand.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new()
+    ///     .kebab_case_subcommands(true)
+    ///     .allow_subcommand_name(&["legacy", "Import_CSV"]);
+    /// ```
+    pub fn allow_subcommand_name(mut self, path: &[&str]) -> Self {
+        self.allowed_subcommand_names
+            .push(path.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    /// Also checks the order items are listed in the rendered `--help`.
+    ///
+    /// This catches reordering introduced by `display_order` or
+    /// `next_display_order`, which the declaration-order checks can't see.
+    /// Off by default, since it renders the help of every command.
+    pub fn rendered_help(mut self, enabled: bool) -> Self {
+        self.rendered_help = enabled;
+        self
+    }
+
+    /// Checks that every command orders its help the same way.
+    ///
+    /// Reports commands where `next_display_order(None)` makes clap list
+    /// items alphabetically while the rest of the tree keeps declaration
+    /// order, or the other way around. This is a tree-wide check, so depth
+    /// overrides don't apply to it.
+    pub fn display_order_consistency(mut self, enabled: bool) -> Self {
+        self.display_order_consistency = enabled;
+        self
+    }
+
+    /// Compares subcommand names and long flags without regard to ASCII case.
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Ignores `-` and `_` when comparing subcommand names and long flags.
+    ///
+    /// With this enabled `--nocolor` sorts before `--no-verify`, as if both
+    /// were written without separators.
+    pub fn ignore_separators(mut self, enabled: bool) -> Self {
+        self.ignore_separators = enabled;
+        self
+    }
+
+    /// Sorts subcommands, short flags and long-only flags in `direction`.
+    ///
+    /// The per-group setters, such as
+    /// [`long_flag_direction`](Self::long_flag_direction), override this for
+    /// one group when called after it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::{Direction, SortConfig};
+    ///
+    /// // Most significant flags last, subcommands still A to Z
+    /// let config = SortConfig::new()
+    ///     .direction(Direction::Descending)
+    ///     .subcommand_direction(Direction::Ascending);
+    /// ```
+    pub fn direction(self, direction: Direction) -> Self {
+        self.subcommand_direction(direction)
+            .short_flag_direction(direction)
+            .long_flag_direction(direction)
+    }
+
+    /// Sorts subcommands in `direction`. Categories keep their configured
+    /// order; only the subcommands within each are affected.
+    pub fn subcommand_direction(mut self, direction: Direction) -> Self {
+        self.subcommand_direction = direction;
+        self
+    }
+
+    /// Sorts flags with short options in `direction`.
+    pub fn short_flag_direction(mut self, direction: Direction) -> Self {
+        self.short_flag_direction = direction;
+        self
+    }
+
+    /// Sorts long-only flags in `direction`.
+    pub fn long_flag_direction(mut self, direction: Direction) -> Self {
+        self.long_flag_direction = direction;
+        self
+    }
+
+    /// Orders subcommands by their display name, set with
+    /// `Command::display_name`, instead of by name. Subcommands without a
+    /// display name use their name.
+    ///
+    /// Ignored and pinned subcommands are then matched by display name too.
+    pub fn display_names(mut self, enabled: bool) -> Self {
+        self.display_names = enabled;
+        self
+    }
+
+    /// Sets where help and version flags are allowed to appear.
+    pub fn builtin_flags(mut self, policy: BuiltinFlags) -> Self {
+        self.builtin_flags = policy;
+        self
+    }
+
+    /// Sets where digit and symbol short flags sort relative to letters.
+    pub fn non_alpha_shorts(mut self, policy: NonAlphaShorts) -> Self {
+        self.non_alpha_shorts = policy;
+        self
+    }
+
+    /// Sets what flags with short options are sorted by.
+    ///
+    /// With [`ShortKey::First`], `-x` with the alias `-a` sorts as `-a`.
+    /// With [`ShortKey::Long`], `-x, --extract` sorts as `extract`.
+    pub fn short_key(mut self, key: ShortKey) -> Self {
+        self.short_key = key;
+        self
+    }
+
+    /// Sets how subcommands are grouped before being sorted.
+    pub fn group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// Orders subcommands with `compare` instead of alphabetically.
+    ///
+    /// The comparator replaces the name comparison, including
+    /// [`group_by`](Self::group_by), [`case_insensitive`](Self::case_insensitive)
+    /// and the [`subcommand_direction`](Self::subcommand_direction).
+    /// Categories, pins and ignored subcommands still apply around it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap::Command;
+    /// use clap_sort::SortConfig;
+    ///
+    /// // `init` first, everything else alphabetical
+    /// let config = SortConfig::new().subcommand_comparator(|a, b| {
+    ///     (a != "init").cmp(&(b != "init")).then_with(|| a.cmp(b))
+    /// });
+    ///
+    /// let cmd = Command::new("mycli")
+    ///     .subcommand(Command::new("init"))
+    ///     .subcommand(Command::new("add"));
+    /// assert!(clap_sort::is_sorted_with_config(&cmd, &config).is_ok());
+    /// ```
+    pub fn subcommand_comparator(mut self, compare: fn(&str, &str) -> std::cmp::Ordering) -> Self {
+        self.subcommand_comparator = Some(compare);
+        self
+    }
+
+    /// Orders long-only flags and long aliases with `compare` instead of
+    /// alphabetically.
+    ///
+    /// The comparator gets the names without their leading `--`, and
+    /// replaces [`case_insensitive`](Self::case_insensitive),
+    /// [`ignore_separators`](Self::ignore_separators) and the
+    /// [`long_flag_direction`](Self::long_flag_direction). Like
+    /// [`subcommand_comparator`](Self::subcommand_comparator), it can plug in
+    /// a locale-aware collator from the calling crate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap::{Arg, Command};
+    /// use clap_sort::SortConfig;
+    ///
+    /// // `ä` sorts with `a`, as in German dictionaries
+    /// let config = SortConfig::new().long_flag_comparator(|a, b| {
+    ///     let key = |name: &str| name.replace('ä', "a");
+    ///     key(a).cmp(&key(b)).then_with(|| a.cmp(b))
+    /// });
+    ///
+    /// let cmd = Command::new("mycli")
+    ///     .arg(Arg::new("änderung").long("änderung"))
+    ///     .arg(Arg::new("bericht").long("bericht"));
+    /// assert!(clap_sort::is_sorted_with_config(&cmd, &config).is_ok());
+    /// assert!(clap_sort::is_sorted(&cmd).is_err());
+    /// ```
+    pub fn long_flag_comparator(mut self, compare: fn(&str, &str) -> std::cmp::Ordering) -> Self {
+        self.long_flag_comparator = Some(compare);
+        self
+    }
+
+    /// Leaves the named subcommands out of the ordering checks.
+    ///
+    /// The subcommands themselves are still validated recursively.
+    pub fn ignore_subcommands(mut self, names: &[&str]) -> Self {
+        self.ignored_subcommands
+            .extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Leaves the arguments with these ids or long flags out of the checks.
+    pub fn ignore_args(mut self, names: &[&str]) -> Self {
+        self.ignored_args
+            .extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Leaves the command at `path` and everything beneath it out of the
+    /// checks, e.g. a grandfathered subcommand tree.
+    ///
+    /// As with [`assert_sorted_at`](crate::assert_sorted_at), `path` starts
+    /// below the root command, so a multicall applet is ignored by its own
+    /// name. The ignored command's own name is still ordered among its
+    /// siblings; use
+    /// [`ignore_subcommands`](Self::ignore_subcommands) to exempt that too.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap::Command;
+    /// use clap_sort::SortConfig;
+    ///
+    /// let cmd = Command::new("mycli").subcommand(
+    ///     Command::new("legacy")
+    ///         .subcommand(Command::new("zap"))
+    ///         .subcommand(Command::new("add")),
+    /// );
+    ///
+    /// let config = SortConfig::new().ignore_path(&["legacy"]);
+    /// assert!(clap_sort::is_sorted_with_config(&cmd, &config).is_ok());
+    /// ```
+    pub fn ignore_path(mut self, path: &[&str]) -> Self {
+        self.ignored_paths
+            .push(path.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    /// Leaves hidden subcommands and arguments out of validation: they
+    /// don't take part in any ordering check or appear in expected orders,
+    /// and hidden subcommands aren't checked recursively.
+    ///
+    /// Use [`hidden_last`](Self::hidden_last) instead to still require them
+    /// to be sorted, after the visible items.
+    pub fn skip_hidden(mut self, enabled: bool) -> Self {
+        self.skip_hidden = enabled;
+        self
+    }
+
+    /// Declares a category of subcommands.
+    ///
+    /// Categories must appear in the order they are declared, and subcommands
+    /// are sorted alphabetically within each category. Subcommands that are
+    /// not in any category come after all categorized ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new()
+    ///     .category("core", &["add", "list", "remove"])
+    ///     .category("admin", &["backup", "restore"]);
+    /// ```
+    pub fn category(mut self, name: &str, commands: &[&str]) -> Self {
+        self.categories.push((
+            name.to_string(),
+            commands.iter().map(|c| c.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Requires the named subcommands, when present, to come first in this order.
+    ///
+    /// Pinned subcommands are exempt from the alphabetical check, which still
+    /// applies to the remaining subcommands.
+    pub fn pin_first(mut self, names: &[&str]) -> Self {
+        self.pinned_first
+            .extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Requires the named subcommands, when present, to come last in this order.
+    ///
+    /// This suits conventional commands such as `help`, `completions` and
+    /// `version`, which many CLIs list at the end.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new().pin_last(&["completions", "help"]);
+    /// ```
+    pub fn pin_last(mut self, names: &[&str]) -> Self {
+        self.pinned_last.extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Requires the subcommands of the command at `path` to be exactly
+    /// `names`, in that order, instead of sorted.
+    ///
+    /// For CLIs whose order is deliberate but still worth enforcing. As with
+    /// [`assert_sorted_at`](crate::assert_sorted_at), `path` starts below the
+    /// root command; an empty path means the root itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config = SortConfig::new().expected_order(&["db"], &["create", "migrate", "seed", "drop"]);
+    /// ```
+    pub fn expected_order(mut self, path: &[&str], names: &[&str]) -> Self {
+        let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+        self.explicit_orders.retain(|(p, _)| *p != path);
+        self.explicit_orders
+            .push((path, names.iter().map(|n| n.to_string()).collect()));
+        self
+    }
+
+    /// Sets the severity of violations of the rule `kind`.
+    ///
+    /// Every rule is an error by default. Warnings show up in
+    /// [`check_all`](crate::check_all) reports but don't fail validation.
+    pub fn severity(mut self, kind: ViolationKind, severity: Severity) -> Self {
+        self.severities.retain(|(k, _)| *k != kind);
+        self.severities.push((kind, severity));
+        self
+    }
+
+    /// Returns the files this configuration was loaded from, nearest first.
+    ///
+    /// Empty for configurations built in code. [`check_all`](crate::check_all)
+    /// copies them into [`Report::config_files`](crate::Report::config_files).
+    pub fn config_files(&self) -> &[std::path::PathBuf] {
+        &self.config_files
+    }
+
+    /// Checks that the settings fit `cmd` and don't contradict each other.
+    ///
+    /// Paths given to [`ignore_path`](Self::ignore_path),
+    /// [`allow_subcommand_name`](Self::allow_subcommand_name) and
+    /// [`expected_order`](Self::expected_order) must name a command in the
+    /// tree, and ids given to [`reserve_short`](Self::reserve_short) an
+    /// argument, so a typo or a renamed subcommand doesn't quietly turn a
+    /// setting off. A subcommand can't be pinned both first and last.
+    /// [`check`](crate::check) runs this before checking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap::Command;
+    /// use clap_sort::SortConfig;
+    ///
+    /// let cmd = Command::new("mycli").subcommand(Command::new("legacy"));
+    ///
+    /// assert!(SortConfig::new().ignore_path(&["legacy"]).validate(&cmd).is_ok());
+    /// assert_eq!(
+    ///     SortConfig::new().ignore_path(&["legasy"]).validate(&cmd).unwrap_err(),
+    ///     "Path 'legasy' in 'ignore-paths' doesn't match any command in 'mycli'"
+    /// );
+    /// ```
+    pub fn validate(&self, cmd: &clap::Command) -> Result<(), String> {
+        let mut paths = Vec::new();
+        let mut ids = Vec::new();
+        collect_paths(cmd, &mut Vec::new(), &mut paths, &mut ids);
+
+        let configs = std::iter::once(self).chain(self.depth_overrides.iter().map(|(_, c)| c));
+        for config in configs {
+            if let Some(name) = config
+                .pinned_first
+                .iter()
+                .find(|name| config.pinned_last.contains(name))
+            {
+                return Err(format!(
+                    "Subcommand '{}' is in both 'pin-first' and 'pin-last'",
+                    name
+                ));
+            }
+
+            let settings = (config.ignored_paths.iter().map(|p| ("ignore-paths", p)))
+                .chain(
+                    config
+                        .allowed_subcommand_names
+                        .iter()
+                        .map(|p| ("allowed-subcommand-names", p)),
+                )
+                .chain(
+                    config
+                        .explicit_orders
+                        .iter()
+                        .map(|(p, _)| ("expected-order", p)),
+                );
+            for (key, path) in settings {
+                if !paths.contains(path) {
+                    return Err(format!(
+                        "Path '{}' in '{}' doesn't match any command in '{}'",
+                        path.join(" "),
+                        key,
+                        cmd.get_name()
+                    ));
+                }
+            }
+
+            if let Some((short, id)) = config
+                .reserved_shorts
+                .iter()
+                .find(|(_, id)| !ids.contains(id))
+            {
+                return Err(format!(
+                    "Argument '{}' reserved for '-{}' isn't declared in '{}'",
+                    id,
+                    short,
+                    cmd.get_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Turns off the rule `kind`, dropping its violations from every report.
+    ///
+    /// Unlike the per-check toggles, this works for any [`ViolationKind`],
+    /// including ones raised by checks that report several kinds.
+    pub fn disable(mut self, kind: ViolationKind) -> Self {
+        if !self.disabled_rules.contains(&kind) {
+            self.disabled_rules.push(kind);
+        }
+        self
+    }
+
+    /// Loads the project's configuration.
+    ///
+    /// With the `toml` feature, this looks for a `clap-sort.toml` in the
+    /// crate being tested (`CARGO_MANIFEST_DIR`, or the current directory
+    /// outside of cargo) and its parent directories up to the workspace
+    /// root, so a workspace can share one file. When several are found, each
+    /// is merged over the ones above it: a crate's file only needs the keys
+    /// it changes, and its values win over the workspace's. The files used
+    /// are listed in [`config_files`](Self::config_files). Without a file, or
+    /// without the feature, it starts from the default configuration.
+    /// `CLAP_SORT_*` environment overrides are applied either way.
+    pub fn discover() -> Result<Self, String> {
+        #[cfg(feature = "toml")]
+        {
+            let start = match std::env::var_os("CARGO_MANIFEST_DIR") {
+                Some(dir) => std::path::PathBuf::from(dir),
+                None => std::env::current_dir()
+                    .map_err(|e| format!("Failed to read current directory: {}", e))?,
+            };
+            let paths = crate::config_file::find_config_files(&start);
+            if !paths.is_empty() {
+                return crate::config_file::from_paths(&paths)?.with_env_overrides();
+            }
+        }
+        Self::new().with_env_overrides()
+    }
+
+    /// Applies overrides from `CLAP_SORT_*` environment variables.
+    ///
+    /// This lets a CI job relax a rule without touching source, e.g.
+    /// `CLAP_SORT_DISABLE=unsorted-long-flags cargo test`. Rules are named
+    /// like in config files, and lists are comma-separated:
+    ///
+    /// - `CLAP_SORT_DISABLE`: rules to [`disable`](Self::disable)
+    /// - `CLAP_SORT_WARN`: rules to downgrade to [`Severity::Warning`]
+    /// - `CLAP_SORT_MAX_VIOLATIONS`: a [`max_violations`](Self::max_violations) cap
+    ///
+    /// [`SortConfig::from_path`] applies these automatically when the `toml`
+    /// feature is enabled.
+    pub fn with_env_overrides(self) -> Result<Self, String> {
+        self.with_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// Applies `CLAP_SORT_*` overrides read through `lookup`.
+    pub(crate) fn with_overrides(
+        mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let rules = |name: &str| -> Result<Vec<ViolationKind>, String> {
+            let Some(value) = lookup(name) else {
+                return Ok(Vec::new());
+            };
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(|rule| {
+                    ViolationKind::from_name(rule)
+                        .ok_or_else(|| format!("Unknown rule '{}' in {}", rule, name))
+                })
+                .collect()
+        };
+
+        // Depth overrides replace the whole config below them, so they need
+        // the rule overrides as well
+        let disabled = rules("CLAP_SORT_DISABLE")?;
+        let warned = rules("CLAP_SORT_WARN")?;
+        let apply = |mut config: SortConfig| {
+            for &kind in &disabled {
+                config = config.disable(kind);
+            }
+            for &kind in &warned {
+                config = config.severity(kind, Severity::Warning);
+            }
+            config
+        };
+        self.depth_overrides = std::mem::take(&mut self.depth_overrides)
+            .into_iter()
+            .map(|(depth, config)| (depth, apply(config)))
+            .collect();
+        self = apply(self);
+        if let Some(max) = lookup("CLAP_SORT_MAX_VIOLATIONS") {
+            let max = max
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid CLAP_SORT_MAX_VIOLATIONS '{}'", max))?;
+            self = self.max_violations(max);
+        }
+
+        Ok(self)
+    }
+
+    /// Keeps at most `max` violations in [`check_all`](crate::check_all)
+    /// reports.
+    ///
+    /// Violations past the cap are counted in
+    /// [`Report::omitted`](crate::Report::omitted) instead of being kept,
+    /// which bounds the output on legacy CLIs with thousands of problems.
+    pub fn max_violations(mut self, max: usize) -> Self {
+        self.max_violations = Some(max);
+        self
+    }
+
+    /// Requires hidden subcommands and flags to come after the visible ones,
+    /// as their own sorted block.
+    ///
+    /// Deprecated-but-present items then still follow a predictable layout.
+    /// By default hidden items are sorted together with visible ones.
+    pub fn hidden_last(mut self, enabled: bool) -> Self {
+        self.hidden_last = enabled;
+        self
+    }
+
+    /// Requires arguments that share a help heading to be declared next to
+    /// each other.
+    ///
+    /// clap groups them under their heading when rendering help anyway, so
+    /// scattered declarations only make the source diverge from the help.
+    pub fn contiguous_headings(mut self, enabled: bool) -> Self {
+        self.contiguous_headings = enabled;
+        self
+    }
+
+    /// Appends the complete expected order of the offending command, all
+    /// subcommands and arguments, to the messages of the `assert_*` and
+    /// `is_sorted*` functions.
+    ///
+    /// Saves re-running with extra tooling to see the target state when a
+    /// failure is deep in a tree.
+    pub fn full_listing(mut self, enabled: bool) -> Self {
+        self.full_listing = enabled;
+        self
+    }
+
+    /// Sets how much of the actual and expected order violation messages
+    /// show. [`Verbosity::Concise`] suits huge commands, where full lists
+    /// are mostly noise.
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Only checks commands down to nesting depth `depth`; deeper ones are
+    /// exempt, e.g. plugin-generated trees.
+    ///
+    /// The root command is at depth 0, so `max_depth(1)` checks the root and
+    /// its direct subcommands.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Returns true if commands at `depth` are past the
+    /// [`max_depth`](Self::max_depth).
+    pub(crate) fn is_too_deep(&self, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max| depth > max)
+    }
+
+    /// Uses `config` for commands at nesting depth `depth` and below.
+    ///
+    /// The root command is at depth 0. When several overrides apply, the one
+    /// with the greatest depth wins. Overrides set on `config` itself are
+    /// ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// // Strict at the top, relaxed where plugin commands live
+    /// let config = SortConfig::new().at_depth(2, SortConfig::new().case_insensitive(true));
+    /// ```
+    pub fn at_depth(mut self, depth: usize, config: SortConfig) -> Self {
+        self.depth_overrides.retain(|(d, _)| *d != depth);
+        self.depth_overrides.push((depth, config));
+        self
+    }
+
+    /// Returns the configuration that applies to commands at `depth`.
+    pub(crate) fn for_depth(&self, depth: usize) -> &SortConfig {
+        self.depth_overrides
+            .iter()
+            .filter(|(d, _)| *d <= depth)
+            .max_by_key(|(d, _)| *d)
+            .map_or(self, |(_, config)| config)
+    }
+
+    /// Drops violations of disabled rules from `violations[start..]` and
+    /// sets the configured severity on the rest.
+    pub(crate) fn apply_severities(&self, violations: &mut Vec<Violation>, start: usize) {
+        let checked: Vec<Violation> = violations
+            .drain(start..)
+            .filter(|v| !self.disabled_rules.contains(&v.kind))
+            .map(|mut v| {
+                v.severity = self.severity_of(v.kind);
+                v
+            })
+            .collect();
+        violations.extend(checked);
+    }
+
+    /// Returns the severity configured for violations of `kind`.
+    pub(crate) fn severity_of(&self, kind: ViolationKind) -> Severity {
+        self.severities
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map_or(Severity::Error, |(_, severity)| *severity)
+    }
+
+    /// Returns true if the subcommand `name` is excluded from ordering checks.
+    pub(crate) fn is_ignored_subcommand(&self, name: &str) -> bool {
+        self.ignored_subcommands.iter().any(|n| n == name)
+    }
+
+    /// Returns true if `arg` is excluded from ordering checks.
+    pub(crate) fn is_ignored_arg(&self, arg: &clap::Arg) -> bool {
+        (self.skip_hidden && arg.is_hide_set())
+            || self
+                .ignored_args
+                .iter()
+                .any(|n| arg.get_id() == n.as_str() || arg.get_long() == Some(n.as_str()))
+    }
+
+    /// Returns true if `cmd` is hidden and hidden subcommands are skipped.
+    pub(crate) fn is_skipped_subcommand(&self, cmd: &clap::Command) -> bool {
+        self.skip_hidden && cmd.is_hide_set()
+    }
+
+    /// Returns true if the command at `path`, which starts below the root,
+    /// is at or beneath an ignored path.
+    pub(crate) fn is_ignored_path<S: AsRef<str>>(&self, path: &[S]) -> bool {
+        self.ignored_paths.iter().any(|ignored| {
+            ignored.len() <= path.len() && ignored.iter().zip(path).all(|(a, b)| a == b.as_ref())
+        })
+    }
+
+    /// Returns true if the subcommand at `path` (below the root) is exempt
+    /// from the naming rules.
+    pub(crate) fn is_allowed_subcommand_name(&self, path: &[&str]) -> bool {
+        self.allowed_subcommand_names
+            .iter()
+            .any(|allowed| allowed.iter().map(String::as_str).eq(path.iter().copied()))
+    }
+
+    /// Returns the order configured for the subcommands of the command at
+    /// `path`, which starts below the root.
+    pub(crate) fn explicit_order<S: AsRef<str>>(&self, path: &[S]) -> Option<&[String]> {
+        self.explicit_orders
+            .iter()
+            .find(|(p, _)| {
+                p.iter()
+                    .map(String::as_str)
+                    .eq(path.iter().map(AsRef::as_ref))
+            })
+            .map(|(_, names)| names.as_slice())
+    }
+
+    /// Returns true if `name` is pinned to the start or end of its list.
+    pub(crate) fn is_pinned(&self, name: &str) -> bool {
+        self.pinned_first
+            .iter()
+            .chain(&self.pinned_last)
+            .any(|p| p == name)
+    }
+
+    /// Returns the name `cmd` is sorted by.
+    pub(crate) fn subcommand_name<'a>(&self, cmd: &'a clap::Command) -> &'a str {
+        match cmd.get_display_name() {
+            Some(display_name) if self.display_names => display_name,
+            _ => cmd.get_name(),
+        }
+    }
+
+    /// Orders two subcommand names, applying any grouping policy.
+    pub(crate) fn compare_subcommands(&self, a: &str, b: &str) -> std::cmp::Ordering {
+        self.category_index(a)
+            .cmp(&self.category_index(b))
+            .then_with(|| self.compare_within_category(a, b))
+    }
+
+    /// Returns the position of the category `name` belongs to, if any.
+    fn category_index(&self, name: &str) -> usize {
+        self.categories
+            .iter()
+            .position(|(_, commands)| commands.iter().any(|c| c == name))
+            .unwrap_or(self.categories.len())
+    }
+
+    fn compare_within_category(&self, a: &str, b: &str) -> std::cmp::Ordering {
+        if let Some(compare) = self.subcommand_comparator {
+            return compare(a, b);
+        }
+        let direction = self.subcommand_direction;
+        match self.group_by {
+            GroupBy::None => self.compare_names(a, b, direction),
+            GroupBy::Prefix(sep) => {
+                let (a_prefix, a_rest) = a.split_once(sep).unwrap_or((a, ""));
+                let (b_prefix, b_rest) = b.split_once(sep).unwrap_or((b, ""));
+                self.compare_names(a_prefix, b_prefix, direction)
+                    .then_with(|| self.compare_names(a_rest, b_rest, direction))
+            }
+        }
+    }
+
+    /// Orders two flags with short options according to the
+    /// [`ShortKey`].
+    pub(crate) fn compare_short_flags(&self, a: &clap::Arg, b: &clap::Arg) -> std::cmp::Ordering {
+        let direction = self.short_flag_direction;
+        if self.short_key == ShortKey::Long {
+            let name = |arg: &clap::Arg| {
+                arg.get_long()
+                    .map(str::to_string)
+                    .or_else(|| arg.get_short().map(String::from))
+                    .unwrap_or_default()
+            };
+            return self.compare_names(&name(a), &name(b), direction);
+        }
+
+        let key = |arg: &clap::Arg| {
+            let short = arg.get_short().unwrap_or_default();
+            match self.short_key {
+                ShortKey::First => std::iter::once(short)
+                    .chain(arg.get_all_short_aliases().unwrap_or_default())
+                    .min_by(|a, b| self.compare_shorts(*a, *b, direction))
+                    .unwrap_or(short),
+                _ => short,
+            }
+        };
+        self.compare_shorts(key(a), key(b), direction)
+    }
+
+    /// Orders two short flags according to this configuration, in
+    /// `direction`.
+    pub(crate) fn compare_shorts(
+        &self,
+        a: char,
+        b: char,
+        direction: Direction,
+    ) -> std::cmp::Ordering {
+        let class = |c: char| {
+            let class = if c.is_alphabetic() {
+                0
+            } else if c.is_numeric() {
+                1
+            } else {
+                2
+            };
+            match self.non_alpha_shorts {
+                NonAlphaShorts::CharOrder => 0,
+                NonAlphaShorts::BeforeLetters => 2 - class,
+                NonAlphaShorts::AfterLetters => class,
+            }
+        };
+
+        direction.apply(
+            class(a)
+                .cmp(&class(b))
+                .then_with(|| a.to_ascii_lowercase().cmp(&b.to_ascii_lowercase()))
+                // Lowercase before uppercase for same letter
+                .then_with(|| b.is_lowercase().cmp(&a.is_lowercase()))
+                .then_with(|| a.cmp(&b)),
+        )
+    }
+
+    /// Orders two long flag names, given without their leading `--`.
+    pub(crate) fn compare_long_flags(&self, a: &str, b: &str) -> std::cmp::Ordering {
+        match self.long_flag_comparator {
+            Some(compare) => compare(a, b),
+            None => self.compare_names(a, b, self.long_flag_direction),
+        }
+    }
+
+    /// Orders two names according to this configuration, in `direction`.
+    pub(crate) fn compare_names(
+        &self,
+        a: &str,
+        b: &str,
+        direction: Direction,
+    ) -> std::cmp::Ordering {
+        let key = |name: &str| -> String {
+            name.chars()
+                .filter(|c| !(self.ignore_separators && (*c == '-' || *c == '_')))
+                .map(|c| {
+                    if self.case_insensitive {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        };
+
+        direction.apply(key(a).cmp(&key(b)).then_with(|| a.cmp(b)))
+    }
+}
+
+/// Collects the path below the root of every command in the tree, and the
+/// id of every argument.
+fn collect_paths(
+    cmd: &clap::Command,
+    path: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+    ids: &mut Vec<String>,
+) {
+    paths.push(path.clone());
+    ids.extend(cmd.get_arguments().map(|arg| arg.get_id().to_string()));
+    for subcmd in cmd.get_subcommands() {
+        path.push(subcmd.get_name().to_string());
+        collect_paths(subcmd, path, paths, ids);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_depth_picks_deepest_applicable_override() {
+        let config = SortConfig::new()
+            .at_depth(1, SortConfig::new().short_flags(false))
+            .at_depth(3, SortConfig::new().long_flags(false));
+
+        assert!(config.for_depth(0).short_flags);
+        assert!(!config.for_depth(1).short_flags);
+        assert!(!config.for_depth(2).short_flags);
+        assert!(config.for_depth(3).short_flags);
+        assert!(!config.for_depth(5).long_flags);
+    }
+
+    #[test]
+    fn test_compare_names_case_insensitive() {
+        use std::cmp::Ordering;
+
+        let strict = SortConfig::new();
+        let relaxed = SortConfig::new().case_insensitive(true);
+
+        assert_eq!(
+            strict.compare_names("Zebra", "alpha", Direction::Ascending),
+            Ordering::Less
+        );
+        assert_eq!(
+            relaxed.compare_names("Zebra", "alpha", Direction::Ascending),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_compare_subcommands_prefix_grouping() {
+        use std::cmp::Ordering;
+
+        let plain = SortConfig::new();
+        let grouped = SortConfig::new().group_by(GroupBy::Prefix(':'));
+
+        assert_eq!(
+            plain.compare_subcommands("db:migrate", "db2"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            grouped.compare_subcommands("db:migrate", "db2"),
+            Ordering::Less
+        );
+        assert_eq!(
+            grouped.compare_subcommands("db", "db:migrate"),
+            Ordering::Less
+        );
+        assert_eq!(
+            grouped.compare_subcommands("db:migrate", "db:seed"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_compare_subcommands_by_category() {
+        use std::cmp::Ordering;
+
+        let config = SortConfig::new()
+            .category("core", &["list", "add"])
+            .category("admin", &["backup"]);
+
+        assert_eq!(config.compare_subcommands("list", "backup"), Ordering::Less);
+        assert_eq!(config.compare_subcommands("add", "list"), Ordering::Less);
+        assert_eq!(
+            config.compare_subcommands("backup", "alpha"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_compare_names_ignore_separators() {
+        use std::cmp::Ordering;
+
+        let strict = SortConfig::new();
+        let relaxed = SortConfig::new().ignore_separators(true);
+
+        assert_eq!(
+            strict.compare_names("no-verify", "nocolor", Direction::Ascending),
+            Ordering::Less
+        );
+        assert_eq!(
+            relaxed.compare_names("no-verify", "nocolor", Direction::Ascending),
+            Ordering::Greater
+        );
+        assert_eq!(
+            relaxed.compare_names("dry-run", "dryrun", Direction::Ascending),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_value_name_style_matches() {
+        assert!(ValueNameStyle::ScreamingSnakeCase.matches("FILE_PATH"));
+        assert!(ValueNameStyle::ScreamingSnakeCase.matches("N2"));
+        assert!(!ValueNameStyle::ScreamingSnakeCase.matches("file_path"));
+        assert!(!ValueNameStyle::ScreamingSnakeCase.matches("FILE-PATH"));
+        assert!(ValueNameStyle::KebabCase.matches("file-path"));
+        assert!(!ValueNameStyle::KebabCase.matches("File-path"));
+        assert!(!ValueNameStyle::KebabCase.matches("file--path"));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let vars = |name: &str| match name {
+            "CLAP_SORT_DISABLE" => Some("alias-collision, unsorted-long-flags".to_string()),
+            "CLAP_SORT_WARN" => Some("unsorted-subcommands".to_string()),
+            "CLAP_SORT_MAX_VIOLATIONS" => Some("10".to_string()),
+            _ => None,
+        };
+        let config = SortConfig::new().with_overrides(vars).unwrap();
+
+        assert_eq!(
+            config.disabled_rules,
+            [
+                ViolationKind::AliasCollision,
+                ViolationKind::UnsortedLongFlags
+            ]
+        );
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedSubcommands),
+            Severity::Warning
+        );
+        assert_eq!(config.max_violations, Some(10));
+
+        let err = SortConfig::new()
+            .with_overrides(|name| (name == "CLAP_SORT_DISABLE").then(|| "alias-order".to_string()))
+            .unwrap_err();
+        assert_eq!(err, "Unknown rule 'alias-order' in CLAP_SORT_DISABLE");
+    }
+
+    #[test]
+    fn test_overrides_apply_below_depth_overrides() {
+        let cmd = clap::Command::new("t").subcommand(
+            clap::Command::new("x")
+                .subcommand(clap::Command::new("b"))
+                .subcommand(clap::Command::new("a")),
+        );
+        let vars = |name: &str| match name {
+            "CLAP_SORT_DISABLE" => Some("unsorted-subcommands".to_string()),
+            "CLAP_SORT_WARN" => Some("unsorted-long-flags".to_string()),
+            _ => None,
+        };
+        let config = SortConfig::new()
+            .at_depth(1, SortConfig::new().case_insensitive(true))
+            .with_overrides(vars)
+            .unwrap();
+
+        assert!(crate::check_all(&cmd, &config).is_ok());
+        assert_eq!(
+            config
+                .for_depth(1)
+                .severity_of(ViolationKind::UnsortedLongFlags),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        use clap::{Arg, Command};
+
+        let cmd = Command::new("t")
+            .arg(Arg::new("verbose").short('v'))
+            .subcommand(Command::new("db").subcommand(Command::new("up")));
+
+        let config = SortConfig::new()
+            .ignore_path(&["db", "up"])
+            .allow_subcommand_name(&["db"])
+            .expected_order(&[], &["db"])
+            .reserve_short('v', "verbose");
+        assert!(config.validate(&cmd).is_ok());
+
+        let err = SortConfig::new()
+            .expected_order(&["up"], &["a"])
+            .validate(&cmd)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Path 'up' in 'expected-order' doesn't match any command in 't'"
+        );
+
+        let err = SortConfig::new()
+            .at_depth(1, SortConfig::new().allow_subcommand_name(&["db", "down"]))
+            .validate(&cmd)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Path 'db down' in 'allowed-subcommand-names' doesn't match any command in 't'"
+        );
+
+        let err = SortConfig::new()
+            .reserve_short('f', "force")
+            .validate(&cmd)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Argument 'force' reserved for '-f' isn't declared in 't'"
+        );
+
+        let err = SortConfig::new()
+            .pin_first(&["help"])
+            .pin_last(&["help"])
+            .validate(&cmd)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Subcommand 'help' is in both 'pin-first' and 'pin-last'"
+        );
+    }
+
+    #[test]
+    fn test_validate_applet_paths() {
+        let cmd = clap::Command::new("busybox")
+            .multicall(true)
+            .subcommand(clap::Command::new("ls"));
+
+        assert!(
+            SortConfig::new()
+                .ignore_path(&["ls"])
+                .validate(&cmd)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,771 @@
+//! Loading a [`SortConfig`] from a TOML file.
+//!
+//! Keys are the builder method names in kebab-case, so the file reads like
+//! the code it replaces:
+//!
+//! ```toml
+//! long-flags = false
+//! builtin-flags = "last"
+//! pin-last = ["help"]
+//! ignore-args = ["verbose"]
+//!
+//! [severity]
+//! unsorted-long-flags = "warning"
+//!
+//! # Or per rule, together with the settings that rule uses
+//! [rules]
+//! variadic-positional = "off"
+//! unsorted-short-flags = { level = "warn", non-alpha-shorts = "after-letters" }
+//!
+//! [depth.2]
+//! case-insensitive = true
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use toml::{Table, Value};
+
+use crate::{
+    BuiltinFlags, Direction, GroupBy, NonAlphaShorts, Severity, ShortKey, SortConfig,
+    ValueNameStyle, Verbosity, ViolationKind,
+};
+
+/// File name [`SortConfig::discover`] looks for.
+const CONFIG_FILE: &str = "clap-sort.toml";
+
+/// Finds the config files in `start` and its ancestors up to the workspace
+/// root, nearest first.
+pub(crate) fn find_config_files(start: &Path) -> Vec<PathBuf> {
+    let root = workspace_root(start).unwrap_or(start);
+    let mut found = Vec::new();
+    for dir in start.ancestors() {
+        let path = dir.join(CONFIG_FILE);
+        if path.is_file() {
+            found.push(path);
+        }
+        if dir == root {
+            break;
+        }
+    }
+    found
+}
+
+/// Finds the workspace `start` belongs to, the way cargo does: the nearest
+/// ancestor whose `Cargo.toml` has a `[workspace]` table, or else the
+/// nearest package. Files further up, such as one in the home directory,
+/// don't belong to the project.
+fn workspace_root(start: &Path) -> Option<&Path> {
+    let mut package = None;
+    for dir in start.ancestors() {
+        let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        if manifest
+            .parse::<Table>()
+            .is_ok_and(|manifest| manifest.contains_key("workspace"))
+        {
+            return Some(dir);
+        }
+        package.get_or_insert(dir);
+    }
+    package
+}
+
+/// Reads `paths`, nearest first, merging each file over the ones further
+/// up so a crate's own settings win over the workspace's.
+pub(crate) fn from_paths(paths: &[PathBuf]) -> Result<SortConfig, String> {
+    let mut merged = Table::new();
+    for path in paths.iter().rev() {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let table: Table = contents
+            .parse()
+            .map_err(|e| format!("{}: Invalid config: {}", path.display(), e))?;
+        merge(&mut merged, table);
+    }
+    let mut config = from_table(&merged)?;
+    config.config_files = paths.to_vec();
+    Ok(config)
+}
+
+/// Merges `over` into `base` key by key, descending into tables such as
+/// `[severity]` and `[rules]` so a crate can change one rule without
+/// restating the others.
+fn merge(base: &mut Table, over: Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(over)) => merge(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+impl SortConfig {
+    /// Reads a configuration from a TOML file such as `clap-sort.toml`.
+    ///
+    /// See [`from_str`](Self::from_str) for the format. `CLAP_SORT_*`
+    /// environment variables are applied on top of the file, see
+    /// [`with_env_overrides`](Self::with_env_overrides).
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let mut config = contents
+            .parse::<SortConfig>()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        config.config_files = vec![path.to_path_buf()];
+        config.with_env_overrides()
+    }
+}
+
+impl FromStr for SortConfig {
+    type Err = String;
+
+    /// Parses a configuration from TOML.
+    ///
+    /// Every builder method has a kebab-case key taking the same value.
+    /// Enums are written in kebab-case (`builtin-flags = "last"`),
+    /// `group-by-prefix` takes the separator, `categories` is an array of
+    /// `{ name, commands }` tables, `disable` lists rule names such as
+    /// `alias-collision`, `[severity]` maps rule names such as
+    /// `unsorted-long-flags` to `"error"` or `"warning"`, and `[depth.N]`
+    /// holds the configuration for [`at_depth(N, ..)`](Self::at_depth).
+    /// Unknown keys are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::SortConfig;
+    ///
+    /// let config: SortConfig = "long-flags = false\npin-last = [\"help\"]".parse().unwrap();
+    /// ```
+    fn from_str(s: &str) -> Result<Self, String> {
+        let table: Table = s.parse().map_err(|e| format!("Invalid config: {}", e))?;
+        from_table(&table)
+    }
+}
+
+fn from_table(table: &Table) -> Result<SortConfig, String> {
+    check_conflicts(table)?;
+    table
+        .iter()
+        .try_fold(SortConfig::new(), |config, (key, value)| {
+            apply(config, key, value)
+        })
+}
+
+/// Applies one top-level key to `config`.
+fn apply(config: SortConfig, key: &str, value: &Value) -> Result<SortConfig, String> {
+    Ok(match key {
+        "subcommands" => config.subcommands(boolean(key, value)?),
+        "short-flags" => config.short_flags(boolean(key, value)?),
+        "long-flags" => config.long_flags(boolean(key, value)?),
+        "group-order" => config.group_order(boolean(key, value)?),
+        "external-subcommands" => config.external_subcommands(boolean(key, value)?),
+        "multicall-applets" => config.multicall_applets(boolean(key, value)?),
+        "alias-collisions" => config.alias_collisions(boolean(key, value)?),
+        "alias-order" => config.alias_order(boolean(key, value)?),
+        "builtin-shadowing" => config.builtin_shadowing(boolean(key, value)?),
+        "variadic-positionals" => config.variadic_positionals(boolean(key, value)?),
+        "positional-order" => config.positional_order(boolean(key, value)?),
+        "value-name-style" => config.value_name_style(match string(key, value)? {
+            "screaming-snake-case" => ValueNameStyle::ScreamingSnakeCase,
+            "kebab-case" => ValueNameStyle::KebabCase,
+            other => return Err(unknown_value(key, other)),
+        }),
+        "possible-values" => config.possible_values(boolean(key, value)?),
+        "kebab-case-subcommands" => config.kebab_case_subcommands(boolean(key, value)?),
+        "max-depth" => config.max_depth(integer(key, value)?),
+        "max-subcommand-len" => config.max_subcommand_len(integer(key, value)?),
+        "allowed-verbs" => config.allowed_verbs(&strings(key, value)?),
+        "allowed-subcommand-names" => {
+            let Value::Array(paths) = value else {
+                return Err(wrong_type(key, "an array of command paths"));
+            };
+            paths.iter().try_fold(config, |config, path| {
+                Ok::<_, String>(config.allow_subcommand_name(&strings(key, path)?))
+            })?
+        }
+        "ignore-paths" => {
+            let Value::Array(paths) = value else {
+                return Err(wrong_type(key, "an array of command paths"));
+            };
+            paths.iter().try_fold(config, |config, path| {
+                Ok::<_, String>(config.ignore_path(&strings(key, path)?))
+            })?
+        }
+        #[cfg(feature = "env")]
+        "env-pattern" => config.env_pattern(string(key, value)?),
+        "rendered-help" => config.rendered_help(boolean(key, value)?),
+        "display-order-consistency" => config.display_order_consistency(boolean(key, value)?),
+        "case-insensitive" => config.case_insensitive(boolean(key, value)?),
+        "ignore-separators" => config.ignore_separators(boolean(key, value)?),
+        "display-names" => config.display_names(boolean(key, value)?),
+        "direction" => config.direction(direction(key, value)?),
+        "subcommand-direction" => config.subcommand_direction(direction(key, value)?),
+        "short-flag-direction" => config.short_flag_direction(direction(key, value)?),
+        "long-flag-direction" => config.long_flag_direction(direction(key, value)?),
+        "builtin-flags" => config.builtin_flags(match string(key, value)? {
+            "ignore" => BuiltinFlags::Ignore,
+            "last" => BuiltinFlags::Last,
+            other => return Err(unknown_value(key, other)),
+        }),
+        "non-alpha-shorts" => config.non_alpha_shorts(match string(key, value)? {
+            "char-order" => NonAlphaShorts::CharOrder,
+            "before-letters" => NonAlphaShorts::BeforeLetters,
+            "after-letters" => NonAlphaShorts::AfterLetters,
+            other => return Err(unknown_value(key, other)),
+        }),
+        "short-key" => config.short_key(match string(key, value)? {
+            "primary" => ShortKey::Primary,
+            "first" => ShortKey::First,
+            "long" => ShortKey::Long,
+            other => return Err(unknown_value(key, other)),
+        }),
+        "group-by-prefix" => {
+            let mut chars = string(key, value)?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(separator), None) => config.group_by(GroupBy::Prefix(separator)),
+                _ => return Err(wrong_type(key, "a single character")),
+            }
+        }
+        "categories" => {
+            let Value::Array(categories) = value else {
+                return Err(wrong_type(key, "an array of tables"));
+            };
+            categories.iter().try_fold(config, |config, category| {
+                let name = category
+                    .get("name")
+                    .ok_or_else(|| wrong_type(key, "tables with a name"))?;
+                let commands = category
+                    .get("commands")
+                    .ok_or_else(|| wrong_type(key, "tables with commands"))?;
+                Ok::<_, String>(config.category(string(key, name)?, &strings(key, commands)?))
+            })?
+        }
+        "ignore-subcommands" => config.ignore_subcommands(&strings(key, value)?),
+        "ignore-args" => config.ignore_args(&strings(key, value)?),
+        "pin-first" => config.pin_first(&strings(key, value)?),
+        "pin-last" => config.pin_last(&strings(key, value)?),
+        "expected-order" => {
+            let Value::Array(orders) = value else {
+                return Err(wrong_type(key, "an array of tables"));
+            };
+            orders.iter().try_fold(config, |config, order| {
+                let field = |name| {
+                    order
+                        .get(name)
+                        .ok_or_else(|| wrong_type(key, "tables with a path and subcommands"))
+                        .and_then(|value| strings(key, value))
+                };
+                Ok::<_, String>(config.expected_order(&field("path")?, &field("subcommands")?))
+            })?
+        }
+        "reserved-shorts" => {
+            let Value::Table(shorts) = value else {
+                return Err(wrong_type(key, "a table"));
+            };
+            shorts.iter().try_fold(config, |config, (short, id)| {
+                let mut chars = short.chars();
+                let (Some(short), None) = (chars.next(), chars.next()) else {
+                    return Err(format!("Invalid short flag '{}' in '{}'", short, key));
+                };
+                Ok(config.reserve_short(short, string(key, id)?))
+            })?
+        }
+        "severity" => {
+            let Value::Table(severities) = value else {
+                return Err(wrong_type(key, "a table"));
+            };
+            severities
+                .iter()
+                .try_fold(config, |config, (rule, severity)| {
+                    let kind = ViolationKind::from_name(rule)
+                        .ok_or_else(|| format!("Unknown rule '{}' in '{}'", rule, key))?;
+                    let severity = match string(rule, severity)? {
+                        "error" => Severity::Error,
+                        "warning" => Severity::Warning,
+                        other => return Err(unknown_value(rule, other)),
+                    };
+                    Ok(config.severity(kind, severity))
+                })?
+        }
+        "disable" => {
+            let Value::Array(rules) = value else {
+                return Err(wrong_type(key, "an array of rule names"));
+            };
+            rules.iter().try_fold(config, |config, rule| {
+                let rule = string(key, rule)?;
+                let kind = ViolationKind::from_name(rule)
+                    .ok_or_else(|| format!("Unknown rule '{}' in '{}'", rule, key))?;
+                Ok::<_, String>(config.disable(kind))
+            })?
+        }
+        "max-violations" => config.max_violations(integer(key, value)?),
+        "full-listing" => config.full_listing(boolean(key, value)?),
+        "hidden-last" => config.hidden_last(boolean(key, value)?),
+        "skip-hidden" => config.skip_hidden(boolean(key, value)?),
+        "contiguous-headings" => config.contiguous_headings(boolean(key, value)?),
+        "verbosity" => config.verbosity(match string(key, value)? {
+            "concise" => Verbosity::Concise,
+            "normal" => Verbosity::Normal,
+            "full" => Verbosity::Full,
+            other => return Err(unknown_value(key, other)),
+        }),
+        "depth" => {
+            let Value::Table(depths) = value else {
+                return Err(wrong_type(key, "a table"));
+            };
+            depths.iter().try_fold(config, |config, (depth, nested)| {
+                let depth = depth
+                    .parse()
+                    .map_err(|_| format!("Invalid depth '{}' in '{}'", depth, key))?;
+                let Value::Table(nested) = nested else {
+                    return Err(wrong_type(key, "a table of tables"));
+                };
+                Ok(config.at_depth(depth, from_table(nested)?))
+            })?
+        }
+        "rules" => {
+            let Value::Table(rules) = value else {
+                return Err(wrong_type(key, "a table"));
+            };
+            rules.iter().try_fold(config, |config, (rule, setting)| {
+                apply_rule(config, rule, setting)
+            })?
+        }
+        _ => return Err(format!("Unknown key '{}'", key)),
+    })
+}
+
+/// Rejects settings made twice in one table, where one would silently win
+/// over the other: a rule's level in `[rules]` as well as in `[severity]` or
+/// `disable`, or a rule option at the top level as well as in the rule's
+/// table.
+fn check_conflicts(table: &Table) -> Result<(), String> {
+    let Some(Value::Table(rules)) = table.get("rules") else {
+        return Ok(());
+    };
+    let severities: Vec<&str> = match table.get("severity") {
+        Some(Value::Table(severities)) => severities.keys().map(String::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let disabled: Vec<&str> = match table.get("disable") {
+        Some(Value::Array(rules)) => rules.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+
+    for (rule, setting) in rules {
+        let has_level = match setting {
+            Value::Table(options) => options.contains_key("level"),
+            _ => true,
+        };
+        if has_level && ViolationKind::from_name(rule).is_some() {
+            for (other_key, others) in [("severity", &severities), ("disable", &disabled)] {
+                if others
+                    .iter()
+                    .any(|other| ViolationKind::from_name(other) == ViolationKind::from_name(rule))
+                {
+                    return Err(format!(
+                        "Rule '{}' is set in both 'rules' and '{}'",
+                        rule, other_key
+                    ));
+                }
+            }
+        }
+        if let Value::Table(options) = setting
+            && let Some(option) = options.keys().find(|key| table.contains_key(*key))
+        {
+            return Err(format!(
+                "'{}' is set both at the top level and for rule '{}'",
+                option, rule
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Top-level keys that can also be set in a rule's table, as options of
+/// that rule.
+///
+/// Only keys that affect nothing but that rule are listed. Settings shared
+/// by several rules, such as `case-insensitive` or `display-names`, stay
+/// top-level so a rule's table can't change the others.
+const RULE_OPTIONS: &[(ViolationKind, &[&str])] = &[
+    (
+        ViolationKind::UnsortedSubcommands,
+        &["categories", "group-by-prefix", "subcommand-direction"],
+    ),
+    (ViolationKind::PinnedSubcommands, &["pin-first", "pin-last"]),
+    (
+        ViolationKind::SubcommandName,
+        &[
+            "allowed-subcommand-names",
+            "kebab-case-subcommands",
+            "max-subcommand-len",
+        ],
+    ),
+    (ViolationKind::SubcommandVerb, &["allowed-verbs"]),
+    (ViolationKind::ReservedShort, &["reserved-shorts"]),
+    (
+        ViolationKind::UnsortedShortFlags,
+        &["non-alpha-shorts", "short-flag-direction", "short-key"],
+    ),
+    (ViolationKind::UnsortedLongFlags, &["long-flag-direction"]),
+    (ViolationKind::BuiltinFlagsNotLast, &["builtin-flags"]),
+    (ViolationKind::ValueName, &["value-name-style"]),
+    #[cfg(feature = "env")]
+    (ViolationKind::EnvName, &["env-pattern"]),
+];
+
+/// Applies one entry of the `[rules]` table: either a level, or a table
+/// with an optional `level` and the rule's options.
+fn apply_rule(config: SortConfig, rule: &str, setting: &Value) -> Result<SortConfig, String> {
+    let kind = ViolationKind::from_name(rule)
+        .ok_or_else(|| format!("Unknown rule '{}' in 'rules'", rule))?;
+
+    let Value::Table(options) = setting else {
+        return level(config, kind, rule, setting);
+    };
+
+    let allowed = RULE_OPTIONS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map_or(&[][..], |(_, options)| options);
+    options.iter().try_fold(config, |config, (key, value)| {
+        if key == "level" {
+            level(config, kind, rule, value)
+        } else if allowed.contains(&key.as_str()) {
+            apply(config, key, value)
+        } else {
+            Err(format!("Unknown option '{}' for rule '{}'", key, rule))
+        }
+    })
+}
+
+/// Turns `kind` off, or sets its severity.
+fn level(
+    config: SortConfig,
+    kind: ViolationKind,
+    rule: &str,
+    value: &Value,
+) -> Result<SortConfig, String> {
+    Ok(match string(rule, value)? {
+        "off" => config.disable(kind),
+        "warn" => config.severity(kind, Severity::Warning),
+        "error" => config.severity(kind, Severity::Error),
+        other => return Err(unknown_value(rule, other)),
+    })
+}
+
+fn boolean(key: &str, value: &Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| wrong_type(key, "a boolean"))
+}
+
+fn integer(key: &str, value: &Value) -> Result<usize, String> {
+    value
+        .as_integer()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| wrong_type(key, "a non-negative integer"))
+}
+
+fn string<'a>(key: &str, value: &'a Value) -> Result<&'a str, String> {
+    value.as_str().ok_or_else(|| wrong_type(key, "a string"))
+}
+
+fn strings<'a>(key: &str, value: &'a Value) -> Result<Vec<&'a str>, String> {
+    value
+        .as_array()
+        .and_then(|items| items.iter().map(Value::as_str).collect())
+        .ok_or_else(|| wrong_type(key, "an array of strings"))
+}
+
+fn direction(key: &str, value: &Value) -> Result<Direction, String> {
+    match string(key, value)? {
+        "ascending" => Ok(Direction::Ascending),
+        "descending" => Ok(Direction::Descending),
+        other => Err(unknown_value(key, other)),
+    }
+}
+
+fn wrong_type(key: &str, expected: &str) -> String {
+    format!("'{}' must be {}", key, expected)
+}
+
+fn unknown_value(key: &str, value: &str) -> String {
+    format!("Unknown value '{}' for '{}'", value, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_every_kind_of_value() {
+        let config: SortConfig = r#"
+long-flags = false
+group-order = true
+builtin-flags = "last"
+builtin-shadowing = false
+display-names = true
+hidden-last = true
+contiguous-headings = true
+direction = "descending"
+short-key = "first"
+value-name-style = "kebab-case"
+max-subcommand-len = 12
+group-by-prefix = ":"
+pin-last = ["help"]
+disable = ["alias-collision"]
+allowed-subcommand-names = [["mycli", "x"]]
+ignore-paths = [["legacy"]]
+categories = [{ name = "Core", commands = ["init"] }]
+expected-order = [{ path = ["db"], subcommands = ["up", "down"] }]
+
+[severity]
+unsorted-long-flags = "warning"
+
+[reserved-shorts]
+v = "verbose"
+
+[depth.2]
+case-insensitive = true
+"#
+        .parse()
+        .unwrap();
+
+        assert!(!config.long_flags);
+        assert!(config.group_order);
+        assert!(!config.builtin_shadowing);
+        assert!(config.display_names);
+        assert!(config.hidden_last);
+        assert!(config.contiguous_headings);
+        assert_eq!(config.subcommand_direction, Direction::Descending);
+        assert_eq!(config.long_flag_direction, Direction::Descending);
+        assert_eq!(config.short_key, ShortKey::First);
+        assert_eq!(config.builtin_flags, BuiltinFlags::Last);
+        assert_eq!(config.value_name_style, Some(ValueNameStyle::KebabCase));
+        assert_eq!(config.max_subcommand_len, Some(12));
+        assert_eq!(config.group_by, GroupBy::Prefix(':'));
+        assert_eq!(config.pinned_last, ["help"]);
+        assert!(config.is_allowed_subcommand_name(&["mycli", "x"]));
+        assert!(config.is_ignored_path(&["legacy", "import"]));
+        assert_eq!(config.categories[0].0, "Core");
+        assert_eq!(config.disabled_rules, [ViolationKind::AliasCollision]);
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedLongFlags),
+            Severity::Warning
+        );
+        assert_eq!(config.reserved_shorts, [('v', "verbose".to_string())]);
+        assert_eq!(config.explicit_order(&["db"]).unwrap(), ["up", "down"]);
+        assert!(config.for_depth(2).case_insensitive);
+        assert!(!config.for_depth(1).case_insensitive);
+    }
+
+    #[test]
+    fn test_rules_table() {
+        let config: SortConfig = r#"
+[rules]
+alias-collision = "off"
+unsorted-long-flags = "warn"
+unsorted-short-flags = { level = "warn", non-alpha-shorts = "after-letters" }
+
+[rules.unsorted-subcommands]
+subcommand-direction = "descending"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.disabled_rules, [ViolationKind::AliasCollision]);
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedLongFlags),
+            Severity::Warning
+        );
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedShortFlags),
+            Severity::Warning
+        );
+        assert_eq!(config.non_alpha_shorts, NonAlphaShorts::AfterLetters);
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedSubcommands),
+            Severity::Error
+        );
+        assert_eq!(config.subcommand_direction, Direction::Descending);
+        assert_eq!(config.short_flag_direction, Direction::Ascending);
+
+        let err = "[rules]\nunsorted-long-flags = \"warning\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(err, "Unknown value 'warning' for 'unsorted-long-flags'");
+
+        let err = "[rules.unsorted-long-flags]\nnon-alpha-shorts = \"after-letters\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Unknown option 'non-alpha-shorts' for rule 'unsorted-long-flags'"
+        );
+
+        // Shared by several rules, so only accepted at the top level
+        let err = "[rules.unsorted-long-flags]\ncase-insensitive = true"
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Unknown option 'case-insensitive' for rule 'unsorted-long-flags'"
+        );
+    }
+
+    #[test]
+    fn test_rejects_conflicting_settings() {
+        let err = "[rules]\nunsorted-long-flags = \"warn\"\n\n[severity]\nCS0008 = \"error\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Rule 'unsorted-long-flags' is set in both 'rules' and 'severity'"
+        );
+
+        let err = "disable = [\"unsorted-aliases\"]\n\n[rules]\nunsorted-aliases = \"warn\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Rule 'unsorted-aliases' is set in both 'rules' and 'disable'"
+        );
+
+        let err = "long-flag-direction = \"ascending\"\n\n[rules.unsorted-long-flags]\nlong-flag-direction = \"descending\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "'long-flag-direction' is set both at the top level and for rule 'unsorted-long-flags'"
+        );
+
+        // Options alone don't set the rule's level
+        let config: SortConfig =
+            "[rules.unsorted-long-flags]\nlong-flag-direction = \"descending\"\n\n[severity]\nunsorted-long-flags = \"warning\""
+                .parse()
+                .unwrap();
+        assert_eq!(
+            config.severity_of(ViolationKind::UnsortedLongFlags),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_rule_options_stay_with_their_rule() {
+        use clap::{Arg, Command};
+
+        let config: SortConfig =
+            "[rules.unsorted-long-flags]\nlong-flag-direction = \"descending\""
+                .parse()
+                .unwrap();
+        assert_eq!(config.subcommand_direction, Direction::Ascending);
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("zone").long("zone"))
+            .arg(Arg::new("color").long("color"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+        assert!(crate::check_all(&cmd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_mistakes() {
+        let err = "short-flag = true".parse::<SortConfig>().unwrap_err();
+        assert_eq!(err, "Unknown key 'short-flag'");
+
+        let err = "long-flags = 1".parse::<SortConfig>().unwrap_err();
+        assert_eq!(err, "'long-flags' must be a boolean");
+
+        let err = "[severity]\nunsorted = \"warning\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(err, "Unknown rule 'unsorted' in 'severity'");
+
+        let err = "builtin-flags = \"first\""
+            .parse::<SortConfig>()
+            .unwrap_err();
+        assert_eq!(err, "Unknown value 'first' for 'builtin-flags'");
+    }
+
+    #[test]
+    fn test_find_config_files_in_ancestors() {
+        let outside = std::env::temp_dir().join(format!("clap-sort-find-{}", std::process::id()));
+        let root = outside.join("workspace");
+        let nested = root.join("crates").join("cli");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(outside.join(CONFIG_FILE), "").unwrap();
+        std::fs::write(root.join(CONFIG_FILE), "").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]").unwrap();
+        std::fs::write(nested.join("Cargo.toml"), "[package]").unwrap();
+
+        let found = find_config_files(&nested);
+        let config = from_paths(&found);
+        std::fs::remove_dir_all(&outside).unwrap();
+        assert_eq!(found, [root.join(CONFIG_FILE)]);
+        assert_eq!(config.unwrap().config_files(), [root.join(CONFIG_FILE)]);
+    }
+
+    #[test]
+    fn test_find_config_files_stops_at_package() {
+        let outside = std::env::temp_dir().join(format!("clap-sort-pkg-{}", std::process::id()));
+        let package = outside.join("cli");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(outside.join(CONFIG_FILE), "").unwrap();
+        std::fs::write(package.join("Cargo.toml"), "[package]").unwrap();
+
+        let found = find_config_files(&package);
+        std::fs::remove_dir_all(&outside).unwrap();
+        assert!(found.is_empty(), "{:?}", found);
+    }
+
+    #[test]
+    fn test_crate_config_merged_over_workspace() {
+        let root = std::env::temp_dir().join(format!("clap-sort-merge-{}", std::process::id()));
+        let nested = root.join("crates").join("cli");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]").unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE),
+            "long-flags = false\ncase-insensitive = true\n\n[severity]\nvalue-name = \"warning\"",
+        )
+        .unwrap();
+        std::fs::write(
+            nested.join(CONFIG_FILE),
+            "long-flags = true\n\n[severity]\nenv-name = \"warning\"",
+        )
+        .unwrap();
+
+        let config = from_paths(&find_config_files(&nested));
+        std::fs::remove_dir_all(&root).unwrap();
+        let config = config.unwrap();
+        assert!(config.long_flags);
+        assert!(config.case_insensitive);
+        assert_eq!(
+            config.severity_of(ViolationKind::ValueName),
+            Severity::Warning
+        );
+        assert_eq!(
+            config.severity_of(ViolationKind::EnvName),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_from_path() {
+        let path = std::env::temp_dir().join(format!("clap-sort-{}.toml", std::process::id()));
+        std::fs::write(&path, "subcommands = false").unwrap();
+
+        let config = SortConfig::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!config.subcommands);
+
+        let err = SortConfig::from_path(&path).unwrap_err();
+        assert!(err.starts_with("Failed to read"), "{}", err);
+    }
+}
@@ -0,0 +1,181 @@
+//! Rendering the difference between an actual and an expected order.
+//!
+//! Short lists are printed in full. For long ones, two complete arrays are
+//! hard to compare by eye, so only the items that have to move are shown,
+//! in their expected place and with a neighbor on each side for context.
+//! [`Verbosity`] can force either extreme.
+
+use crate::Verbosity;
+
+/// Lists longer than this are shown as a diff instead of in full.
+const LONG_LIST: usize = 10;
+
+/// Describes how `actual` differs from `expected`, as the lines that follow
+/// a violation's headline. `labels` name the actual and expected orders.
+pub(crate) fn order_details<S: AsRef<str>>(
+    actual: &[S],
+    expected: &[S],
+    labels: (&str, &str),
+    verbosity: Verbosity,
+) -> String {
+    let actual: Vec<&str> = actual.iter().map(AsRef::as_ref).collect();
+    let expected: Vec<&str> = expected.iter().map(AsRef::as_ref).collect();
+
+    match verbosity {
+        Verbosity::Concise => return neighbors(&actual, &expected),
+        Verbosity::Normal if expected.len() > LONG_LIST => {}
+        Verbosity::Normal | Verbosity::Full => {
+            return format!("{}: {:?}\n{}: {:?}", labels.0, actual, labels.1, expected);
+        }
+    }
+
+    let moved = moved_items(&actual, &expected);
+    let count = moved.iter().filter(|m| **m).count();
+
+    let mut out = format!(
+        "{} ({} of {} moved, marked +):",
+        labels.1,
+        count,
+        expected.len()
+    );
+    let mut skipped = false;
+    for (i, item) in expected.iter().enumerate() {
+        let near_move = moved[i.saturating_sub(1)..=(i + 1).min(expected.len() - 1)]
+            .iter()
+            .any(|m| *m);
+        if !near_move {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            out.push_str("\n  ...");
+            skipped = false;
+        }
+        let marker = if moved[i] { "+" } else { " " };
+        out.push_str(&format!("\n  {} {}", marker, item));
+    }
+    if skipped {
+        out.push_str("\n  ...");
+    }
+    out
+}
+
+/// Says where each item that has to move belongs, relative to its
+/// neighbors in `expected`.
+fn neighbors(actual: &[&str], expected: &[&str]) -> String {
+    let moved = moved_items(actual, expected);
+    let lines: Vec<String> = (0..expected.len())
+        .filter(|&i| moved[i])
+        .map(|i| {
+            let before = i.checked_sub(1).map(|j| expected[j]);
+            let after = expected.get(i + 1);
+            match (before, after) {
+                (Some(before), Some(after)) => format!(
+                    "{} should come after {} and before {}",
+                    expected[i], before, after
+                ),
+                (Some(before), None) => {
+                    format!("{} should come last, after {}", expected[i], before)
+                }
+                (None, Some(after)) => {
+                    format!("{} should come first, before {}", expected[i], after)
+                }
+                (None, None) => format!("{} is out of place", expected[i]),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Marks the items of `expected` that are not part of the longest common
+/// subsequence with `actual`, i.e. the fewest items that have to move.
+fn moved_items(actual: &[&str], expected: &[&str]) -> Vec<bool> {
+    // lengths[i][j] is the LCS length of actual[i..] and expected[j..]
+    let mut lengths = vec![vec![0usize; expected.len() + 1]; actual.len() + 1];
+    for i in (0..actual.len()).rev() {
+        for j in (0..expected.len()).rev() {
+            lengths[i][j] = if actual[i] == expected[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut moved = vec![true; expected.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < actual.len() && j < expected.len() {
+        if actual[i] == expected[j] {
+            moved[j] = false;
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_lists_are_shown_in_full() {
+        let details = order_details(
+            &["b", "a"],
+            &["a", "b"],
+            ("Actual", "Expected"),
+            Verbosity::Normal,
+        );
+        assert_eq!(details, "Actual: [\"b\", \"a\"]\nExpected: [\"a\", \"b\"]");
+    }
+
+    #[test]
+    fn test_long_lists_show_moved_items_with_context() {
+        let expected: Vec<String> = (b'a'..=b'p').map(|c| (c as char).to_string()).collect();
+        let mut actual = expected.clone();
+        let g = actual.remove(6);
+        actual.push(g);
+
+        let labels = ("Actual order", "Expected order");
+        let details = order_details(&actual, &expected, labels, Verbosity::Normal);
+        assert_eq!(
+            details,
+            "Expected order (1 of 16 moved, marked +):\n  ...\n    f\n  + g\n    h\n  ..."
+        );
+
+        let details = order_details(&actual, &expected, labels, Verbosity::Full);
+        assert!(details.starts_with("Actual order: [\"a\""));
+    }
+
+    #[test]
+    fn test_concise_shows_neighbors_of_moved_items() {
+        let details = order_details(
+            &["-r", "-m", "-o", "-a"],
+            &["-a", "-m", "-o", "-r"],
+            ("Actual", "Expected"),
+            Verbosity::Concise,
+        );
+        assert_eq!(
+            details,
+            "-a should come first, before -m\n-r should come last, after -o"
+        );
+
+        let details = order_details(
+            &["-a", "-o", "-r", "-m"],
+            &["-a", "-m", "-o", "-r"],
+            ("Actual", "Expected"),
+            Verbosity::Concise,
+        );
+        assert_eq!(details, "-m should come after -a and before -o");
+    }
+
+    #[test]
+    fn test_moved_items_are_minimal() {
+        let moved = moved_items(&["b", "c", "d", "a"], &["a", "b", "c", "d"]);
+        assert_eq!(moved, [true, false, false, false]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Consistency of display ordering across a command tree.
+//!
+//! clap gives every flag and subcommand an increasing `display_order` as it
+//! is added, unless `next_display_order(None)` was called first, in which
+//! case clap lists those items alphabetically in help. Mixing both styles in
+//! one CLI makes some help screens declaration-ordered and others not.
+
+use crate::rules::is_builtin_flag;
+use crate::{Violation, ViolationKind};
+
+/// Display order clap reports for items without one.
+const UNSET: usize = 999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// Every item has a display order, so help follows declaration order.
+    Declared,
+    /// No item has a display order, so clap sorts help alphabetically.
+    Alphabetical,
+}
+
+impl std::fmt::Display for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Style::Declared => write!(f, "declaration order"),
+            Style::Alphabetical => write!(f, "alphabetical order (next_display_order(None))"),
+        }
+    }
+}
+
+/// Checks that every command in the tree orders its help the same way.
+///
+/// The first command with two or more flags or subcommands sets the style
+/// the rest of the tree is compared against.
+pub(crate) fn check_display_order(
+    cmd: &clap::Command,
+    parent_path: Vec<&str>,
+    out: &mut Vec<Violation>,
+) {
+    let mut first = None;
+    check_command(cmd, parent_path, &mut first, out);
+}
+
+fn check_command<'a>(
+    cmd: &'a clap::Command,
+    parent_path: Vec<&'a str>,
+    first: &mut Option<(String, Style)>,
+    out: &mut Vec<Violation>,
+) {
+    let mut current_path = parent_path;
+    current_path.push(cmd.get_name());
+    let path = current_path.join(" ");
+
+    let flags: Vec<usize> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_positional() && !is_builtin_flag(a))
+        .map(|a| a.get_display_order())
+        .collect();
+    let subcommands: Vec<usize> = cmd
+        .get_subcommands()
+        .map(|s| s.get_display_order())
+        .collect();
+
+    for (kind, orders) in [("Flags", flags), ("Subcommands", subcommands)] {
+        let Some(style) = style(&orders) else {
+            out.push(Violation::new(
+                ViolationKind::InconsistentDisplayOrder,
+                &current_path,
+                format!(
+                    "{} in '{}' mix explicit and reset display orders!",
+                    kind, path
+                ),
+            ));
+            continue;
+        };
+        // A single item looks the same either way
+        if orders.len() < 2 {
+            continue;
+        }
+
+        match first {
+            None => *first = Some((path.clone(), style)),
+            Some((first_path, first_style)) if *first_style != style => {
+                out.push(Violation::new(
+                    ViolationKind::InconsistentDisplayOrder,
+                    &current_path,
+                    format!(
+                        "{} in '{}' are shown in {}, but '{}' uses {}!",
+                        kind, path, style, first_path, first_style
+                    ),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for subcmd in cmd.get_subcommands() {
+        check_command(subcmd, current_path.clone(), first, out);
+    }
+}
+
+/// Classifies a list of display orders, or `None` if it mixes both styles.
+fn style(orders: &[usize]) -> Option<Style> {
+    let unset = orders.iter().filter(|o| **o == UNSET).count();
+    if unset == 0 {
+        Some(Style::Declared)
+    } else if unset == orders.len() {
+        Some(Style::Alphabetical)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, Command};
+
+    #[test]
+    fn test_consistent_declaration_order() {
+        let cmd = Command::new("test")
+            .arg(Arg::new("all").short('a').long("all"))
+            .arg(Arg::new("output").short('o').long("output"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        let mut out = Vec::new();
+        check_display_order(&cmd, vec![], &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_reset_in_one_subcommand() {
+        let cmd = Command::new("test")
+            .arg(Arg::new("all").short('a').long("all"))
+            .arg(Arg::new("output").short('o').long("output"))
+            .subcommand(
+                Command::new("sub")
+                    .next_display_order(None)
+                    .arg(Arg::new("debug").short('d').long("debug"))
+                    .arg(Arg::new("force").short('f').long("force")),
+            );
+
+        let mut out = Vec::new();
+        check_display_order(&cmd, vec![], &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].path, ["test", "sub"]);
+        assert!(
+            out[0]
+                .message
+                .starts_with("Flags in 'test sub' are shown in alphabetical order"),
+            "{}",
+            out[0]
+        );
+    }
+
+    #[test]
+    fn test_reset_part_way_through_command() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .next_display_order(None)
+            .subcommand(Command::new("list"));
+
+        let mut out = Vec::new();
+        check_display_order(&cmd, vec![], &mut out);
+        assert!(
+            out[0].message.contains("mix explicit and reset"),
+            "{}",
+            out[0]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Extension traits for calling clap-sort from clap types directly.
+
+use crate::SortConfig;
+
+/// Sorting methods on [`clap::Command`].
+pub trait CommandExt: Sized {
+    /// Sorts the subcommands and arguments of this command and all nested
+    /// commands, like [`sort_command`](crate::sort_command).
+    ///
+    /// In debug builds with the `assert` feature, this also asserts that the
+    /// rendered help comes out sorted, the same check as
+    /// [`SortConfig::rendered_help`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap::Command;
+    /// use clap_sort::CommandExt;
+    ///
+    /// let mut cmd = Command::new("mycli")
+    ///     .subcommand(Command::new("list"))
+    ///     .subcommand(Command::new("add"))
+    ///     .sorted();
+    ///
+    /// let help = cmd.render_help().to_string();
+    /// assert!(help.find("add").unwrap() < help.find("list").unwrap());
+    /// ```
+    fn sorted(self) -> Self;
+}
+
+impl CommandExt for clap::Command {
+    fn sorted(self) -> Self {
+        let cmd = crate::sort::sort_command(self, &SortConfig::default());
+
+        #[cfg(all(debug_assertions, feature = "assert"))]
+        {
+            let report = crate::check_all(&cmd, &SortConfig::new().rendered_help(true));
+            let unsorted: Vec<String> = report
+                .errors
+                .iter()
+                .filter(|v| v.kind == crate::ViolationKind::RenderedHelpOrder)
+                .map(|v| v.message.clone())
+                .collect();
+            assert!(unsorted.is_empty(), "{}", unsorted.join("\n"));
+        }
+
+        cmd
+    }
+}
+
+/// Validated parsing for [`clap::Parser`] types.
+///
+/// For CLIs without a test suite around them: the first run of a debug
+/// build checks the command against the project's configuration, from
+/// [`SortConfig::discover`], before parsing. Release builds only parse.
+#[cfg(feature = "assert")]
+pub trait ParserExt: clap::Parser {
+    /// Like [`clap::Parser::parse`], but first checks that the command is
+    /// sorted in debug builds.
+    ///
+    /// # Panics
+    /// With debug assertions, panics if the configuration can't be loaded
+    /// or the command is not properly sorted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use clap::Parser;
+    /// use clap_sort::ParserExt;
+    ///
+    /// #[derive(Parser)]
+    /// struct Cli {
+    ///     #[arg(short, long)]
+    ///     verbose: bool,
+    /// }
+    ///
+    /// let cli = Cli::parse_validated();
+    /// ```
+    #[track_caller]
+    fn parse_validated() -> Self {
+        validate::<Self>();
+        Self::parse()
+    }
+
+    /// Like [`clap::Parser::parse_from`], but first checks that the command
+    /// is sorted in debug builds.
+    ///
+    /// # Panics
+    /// With debug assertions, panics if the configuration can't be loaded
+    /// or the command is not properly sorted.
+    #[track_caller]
+    fn parse_validated_from<I, T>(itr: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        validate::<Self>();
+        Self::parse_from(itr)
+    }
+}
+
+#[cfg(feature = "assert")]
+impl<P: clap::Parser> ParserExt for P {}
+
+#[cfg(feature = "assert")]
+#[track_caller]
+fn validate<P: clap::Parser>() {
+    #[cfg(debug_assertions)]
+    if let Err(msg) = SortConfig::discover()
+        .and_then(|config| crate::is_sorted_with_config(&P::command(), &config))
+    {
+        crate::fail(msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, Command};
+
+    #[derive(clap::Parser)]
+    struct Sorted {
+        #[arg(short, long)]
+        debug: bool,
+        #[arg(short, long)]
+        verbose: bool,
+    }
+
+    #[derive(clap::Parser)]
+    struct Unsorted {
+        #[arg(short, long)]
+        verbose: bool,
+        #[arg(short, long)]
+        debug: bool,
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_parse_validated() {
+        let cli = Sorted::parse_validated_from(["test", "-v"]);
+        assert!(cli.verbose);
+        assert!(!cli.debug);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "Flags with short options"))]
+    fn test_parse_validated_unsorted() {
+        let cli = Unsorted::parse_validated_from(["test", "-d"]);
+        assert!(cli.debug);
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut cmd = Command::new("test")
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("debug").short('d'))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add").subcommand(Command::new("zebra")))
+            .sorted();
+
+        let help = cmd.render_help().to_string();
+        assert!(help.find("add").unwrap() < help.find("list").unwrap());
+        assert!(help.find("-d").unwrap() < help.find("-v").unwrap());
+    }
+}
@@ -0,0 +1,524 @@
+//! Concrete reorderings that would make a command tree pass the checks.
+//!
+//! Items that are ignored, positional, or belong to a disabled check keep
+//! their place. Everything else is moved into the slots its group already
+//! occupies, so short flags stay where short flags were, long-only flags
+//! where long-only flags were, and so on.
+
+use crate::rules::{arg_group, flag_display, is_builtin_flag};
+use crate::{BuiltinFlags, SortConfig};
+
+/// Every reordering needed to sort a command tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixPlan {
+    /// Commands that need changes, in the order they were visited.
+    pub commands: Vec<CommandFix>,
+}
+
+impl FixPlan {
+    /// Returns true if nothing needs to move.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// The reorderings needed within a single command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandFix {
+    /// Full command path from the root, e.g. `["mycli", "generate"]`.
+    pub path: Vec<String>,
+    /// Moves of subcommands, indexed into `get_subcommands()`.
+    pub subcommands: Vec<Move>,
+    /// Moves of arguments, indexed into `get_arguments()`.
+    pub args: Vec<Move>,
+}
+
+/// One item moving from its current index to its target index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    /// Subcommand name, or the flag as shown in help (`-v` or `--config`).
+    pub name: String,
+    /// Current index.
+    pub from: usize,
+    /// Index the item should be moved to.
+    pub to: usize,
+}
+
+/// The canonical order of the items in a single command.
+///
+/// Flags are listed as shown in help (`-v` or `--config`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedOrder {
+    /// Full command path from the root, e.g. `["mycli", "generate"]`.
+    pub path: Vec<String>,
+    /// Subcommand names.
+    pub subcommands: Vec<String>,
+    /// Positional arguments, which keep their declared order.
+    pub positionals: Vec<String>,
+    /// Flags with a short option.
+    pub short_flags: Vec<String>,
+    /// Long-only flags.
+    pub long_flags: Vec<String>,
+    /// `--help` and `--version`, with [`BuiltinFlags::Last`].
+    pub builtin_flags: Vec<String>,
+    /// Arguments no check applies to, in their declared place.
+    pub unchecked: Vec<String>,
+}
+
+/// Adds the fixes for `cmd` and its subcommands to `out`.
+///
+/// `in_applet` is set below a multicall command, where `parent_path` starts
+/// at the applet rather than the root.
+pub(crate) fn plan_command<'a>(
+    cmd: &'a clap::Command,
+    parent_path: Vec<&'a str>,
+    in_applet: bool,
+    config: &SortConfig,
+    out: &mut Vec<CommandFix>,
+) {
+    let mut current_path = parent_path.clone();
+    current_path.push(cmd.get_name());
+    if config.is_ignored_path(crate::config_path(&current_path, in_applet))
+        || config.is_skipped_subcommand(cmd)
+        || config.is_too_deep(parent_path.len())
+    {
+        return;
+    }
+
+    let root_config = config;
+    let config = root_config.for_depth(parent_path.len());
+
+    let names: Vec<&str> = cmd.get_subcommands().map(|s| s.get_name()).collect();
+    let subcommands = moves(&subcommand_order(cmd, &current_path, config), |i| {
+        names[i].to_string()
+    });
+
+    let args: Vec<&clap::Arg> = cmd.get_arguments().collect();
+    let arg_moves = moves(&arg_order(cmd, config), |i| flag_display(args[i]));
+
+    if !subcommands.is_empty() || !arg_moves.is_empty() {
+        out.push(CommandFix {
+            path: current_path.iter().map(|p| p.to_string()).collect(),
+            subcommands,
+            args: arg_moves,
+        });
+    }
+
+    // Multicall applets are each their own root
+    for subcmd in cmd.get_subcommands() {
+        let parent_path = if cmd.is_multicall_set() {
+            vec![]
+        } else {
+            current_path.clone()
+        };
+        plan_command(
+            subcmd,
+            parent_path,
+            in_applet || cmd.is_multicall_set(),
+            root_config,
+            out,
+        );
+    }
+}
+
+/// Describes the complete expected order of the command at `path`, found
+/// in the tree rooted at `cmd`.
+pub(crate) fn expected_listing(
+    cmd: &clap::Command,
+    parent_path: Vec<&str>,
+    path: &[String],
+    config: &SortConfig,
+) -> Option<String> {
+    let mut current_path = parent_path.clone();
+    current_path.push(cmd.get_name());
+
+    if current_path != path {
+        // Multicall applets are each their own root
+        return cmd.get_subcommands().find_map(|subcmd| {
+            let parent_path = if cmd.is_multicall_set() {
+                vec![]
+            } else {
+                current_path.clone()
+            };
+            expected_listing(subcmd, parent_path, path, config)
+        });
+    }
+
+    let expected = expected_order(cmd, &current_path, config.for_depth(parent_path.len()));
+    let mut listing = format!("Expected order of '{}':", current_path.join(" "));
+    let groups = [
+        ("Subcommands", &expected.subcommands),
+        ("Positionals", &expected.positionals),
+        ("Short flags", &expected.short_flags),
+        ("Long flags", &expected.long_flags),
+        ("Built-in flags", &expected.builtin_flags),
+        ("Unchecked", &expected.unchecked),
+    ];
+    for (name, group) in groups {
+        if !group.is_empty() {
+            listing.push_str(&format!("\n  {}: {:?}", name, group));
+        }
+    }
+
+    Some(listing)
+}
+
+/// Returns the expected order of the items in `cmd`, whose full path is
+/// `path`.
+pub(crate) fn expected_order(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+) -> ExpectedOrder {
+    let names: Vec<&str> = cmd.get_subcommands().map(|s| s.get_name()).collect();
+    let mut expected = ExpectedOrder {
+        path: path.iter().map(|p| p.to_string()).collect(),
+        subcommands: subcommand_order(cmd, path, config)
+            .into_iter()
+            .map(|i| names[i].to_string())
+            .collect(),
+        ..ExpectedOrder::default()
+    };
+
+    let args: Vec<&clap::Arg> = cmd.get_arguments().collect();
+    for i in arg_order(cmd, config) {
+        let arg = args[i];
+        let group = if arg.is_positional() {
+            &mut expected.positionals
+        } else if config.is_ignored_arg(arg) {
+            &mut expected.unchecked
+        } else if config.builtin_flags == BuiltinFlags::Last && is_builtin_flag(arg) {
+            &mut expected.builtin_flags
+        } else if arg.get_short().is_some() {
+            &mut expected.short_flags
+        } else {
+            &mut expected.long_flags
+        };
+        group.push(flag_display(arg));
+    }
+
+    expected
+}
+
+/// Returns the current index of the subcommand that belongs at each position.
+///
+/// `path` is the full path of `cmd`, used to look up an explicit order.
+pub(crate) fn subcommand_order<S: AsRef<str>>(
+    cmd: &clap::Command,
+    path: &[S],
+    config: &SortConfig,
+) -> Vec<usize> {
+    let subcommands: Vec<&clap::Command> = cmd.get_subcommands().collect();
+    let names: Vec<&str> = subcommands
+        .iter()
+        .map(|s| config.subcommand_name(s))
+        .collect();
+    let mut order: Vec<usize> = (0..names.len()).collect();
+
+    let enabled = if cmd.is_multicall_set() {
+        config.multicall_applets
+    } else {
+        config.subcommands
+    };
+    if !enabled && !config.hidden_last {
+        return order;
+    }
+
+    // Listed subcommands go first, in the listed order; any others keep
+    // their relative order after them. Skipped hidden subcommands aren't
+    // part of the check, so they keep their place
+    if let Some(expected) = config.explicit_order(&path[1..]) {
+        let slots: Vec<usize> = (0..names.len())
+            .filter(|&i| !config.is_skipped_subcommand(subcommands[i]))
+            .collect();
+        let mut sorted = slots.clone();
+        sorted.sort_by_key(|&i| {
+            expected
+                .iter()
+                .position(|name| name == names[i])
+                .unwrap_or(expected.len())
+        });
+        fill(&mut order, &slots, &sorted);
+        return order;
+    }
+
+    let slots: Vec<usize> = (0..names.len())
+        .filter(|&i| {
+            !config.is_ignored_subcommand(names[i]) && !config.is_skipped_subcommand(subcommands[i])
+        })
+        .collect();
+
+    let sort_block = |block: Vec<usize>| -> Vec<usize> {
+        if !enabled {
+            return block;
+        }
+
+        let mut unpinned: Vec<usize> = block
+            .iter()
+            .copied()
+            .filter(|&i| !config.is_pinned(names[i]))
+            .collect();
+        unpinned.sort_by(|&a, &b| config.compare_subcommands(names[a], names[b]));
+
+        let present = |pins: &[String]| -> Vec<usize> {
+            pins.iter()
+                .filter_map(|pin| block.iter().copied().find(|&i| names[i] == pin))
+                .collect()
+        };
+        let mut sorted = present(&config.pinned_first);
+        sorted.extend(unpinned);
+        sorted.extend(present(&config.pinned_last));
+        sorted
+    };
+
+    // Hidden subcommands form their own sorted block after the visible ones
+    let sorted = if config.hidden_last {
+        let (visible, hidden): (Vec<usize>, Vec<usize>) =
+            slots.iter().partition(|&&i| !subcommands[i].is_hide_set());
+        let mut sorted = sort_block(visible);
+        sorted.extend(sort_block(hidden));
+        sorted
+    } else {
+        sort_block(slots.clone())
+    };
+
+    fill(&mut order, &slots, &sorted);
+    order
+}
+
+/// Returns the current index of the argument that belongs at each position.
+pub(crate) fn arg_order(cmd: &clap::Command, config: &SortConfig) -> Vec<usize> {
+    let args: Vec<&clap::Arg> = cmd.get_arguments().collect();
+    let mut order: Vec<usize> = (0..args.len()).collect();
+
+    let flags: Vec<usize> = (0..args.len())
+        .filter(|&i| !args[i].is_positional() && !config.is_ignored_arg(args[i]))
+        .collect();
+
+    // Hidden flags are sorted among themselves when they form their own block
+    let blocks: Vec<Vec<usize>> = if config.hidden_last {
+        let (visible, hidden) = flags.iter().partition(|&&i| !args[i].is_hide_set());
+        vec![visible, hidden]
+    } else {
+        vec![flags.clone()]
+    };
+
+    for block in &blocks {
+        if config.short_flags {
+            let slots: Vec<usize> = block
+                .iter()
+                .copied()
+                .filter(|&i| !is_builtin_flag(args[i]) && args[i].get_short().is_some())
+                .collect();
+            let mut sorted = slots.clone();
+            sorted.sort_by(|&a, &b| config.compare_short_flags(args[a], args[b]));
+            fill(&mut order, &slots, &sorted);
+        }
+
+        if config.long_flags {
+            let slots: Vec<usize> = block
+                .iter()
+                .copied()
+                .filter(|&i| {
+                    !is_builtin_flag(args[i])
+                        && args[i].get_short().is_none()
+                        && args[i].get_long().is_some()
+                })
+                .collect();
+            let mut sorted = slots.clone();
+            sorted.sort_by(|&a, &b| {
+                config.compare_long_flags(args[a].get_long().unwrap(), args[b].get_long().unwrap())
+            });
+            fill(&mut order, &slots, &sorted);
+        }
+    }
+
+    if config.builtin_flags == BuiltinFlags::Last {
+        let (mut others, builtins): (Vec<usize>, Vec<usize>) = flags
+            .iter()
+            .map(|&slot| order[slot])
+            .partition(|&i| !is_builtin_flag(args[i]));
+        others.extend(builtins);
+        fill(&mut order, &flags, &others);
+    }
+
+    if config.hidden_last {
+        let (mut visible, hidden): (Vec<usize>, Vec<usize>) = flags
+            .iter()
+            .map(|&slot| order[slot])
+            .partition(|&i| !args[i].is_hide_set());
+        visible.extend(hidden);
+        fill(&mut order, &flags, &visible);
+    }
+
+    if config.group_order {
+        let slots: Vec<usize> = (0..args.len())
+            .filter(|&slot| {
+                let arg = args[order[slot]];
+                !config.is_ignored_arg(arg) && !is_builtin_flag(arg) && arg_group(arg).is_some()
+            })
+            .collect();
+        let mut grouped: Vec<usize> = slots.iter().map(|&slot| order[slot]).collect();
+        grouped.sort_by_key(|&i| {
+            (
+                config.hidden_last && args[i].is_hide_set(),
+                arg_group(args[i]),
+            )
+        });
+        fill(&mut order, &slots, &grouped);
+    }
+
+    order
+}
+
+/// Like [`arg_order`], but also groups the arguments as with
+/// [`SortConfig::group_order`]: positionals, then short flags, then
+/// long-only flags.
+///
+/// Generated definitions use this canonical order, whether or not the
+/// checks require it.
+pub(crate) fn grouped_arg_order(cmd: &clap::Command, config: &SortConfig) -> Vec<usize> {
+    arg_order(cmd, &config.clone().group_order(true))
+}
+
+/// Places `sorted` into the positions listed in `slots`.
+fn fill(order: &mut [usize], slots: &[usize], sorted: &[usize]) {
+    for (&slot, &item) in slots.iter().zip(sorted) {
+        order[slot] = item;
+    }
+}
+
+/// Turns a target order into the moves of the items that change place.
+fn moves(order: &[usize], name: impl Fn(usize) -> String) -> Vec<Move> {
+    let mut moves: Vec<Move> = order
+        .iter()
+        .enumerate()
+        .filter(|(to, from)| *to != **from)
+        .map(|(to, &from)| Move {
+            name: name(from),
+            from,
+            to,
+        })
+        .collect();
+    moves.sort_by_key(|m| m.from);
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn plan(cmd: &Command, config: &SortConfig) -> Vec<CommandFix> {
+        let mut out = Vec::new();
+        plan_command(cmd, vec![], false, config, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_sorted_tree_needs_no_moves() {
+        let cmd = Command::new("test")
+            .arg(Arg::new("file"))
+            .arg(Arg::new("output").short('o').long("output"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        assert!(plan(&cmd, &SortConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_subcommand_moves() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("help"))
+            .subcommand(Command::new("remove"));
+
+        let plan = plan(&cmd, &SortConfig::new().pin_last(&["help"]));
+
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].path, ["test"]);
+        let moves: Vec<_> = plan[0]
+            .subcommands
+            .iter()
+            .map(|m| (m.name.as_str(), m.from, m.to))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                ("list", 0, 1),
+                ("add", 1, 0),
+                ("help", 2, 3),
+                ("remove", 3, 2)
+            ]
+        );
+        assert!(plan[0].args.is_empty());
+    }
+
+    #[test]
+    fn test_flags_keep_their_group_slots() {
+        let cmd = Command::new("test")
+            .arg(Arg::new("verbose").short('v').long("verbose"))
+            .arg(Arg::new("file"))
+            .arg(Arg::new("zone").long("zone"))
+            .arg(Arg::new("debug").short('d').long("debug"))
+            .arg(Arg::new("color").long("color"));
+
+        let plan = plan(&cmd, &SortConfig::default());
+
+        let moves: Vec<_> = plan[0]
+            .args
+            .iter()
+            .map(|m| (m.name.as_str(), m.from, m.to))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                ("-v", 0, 3),
+                ("--zone", 2, 4),
+                ("-d", 3, 0),
+                ("--color", 4, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builtin_flags_moved_last() {
+        let cmd = Command::new("test")
+            .disable_help_flag(true)
+            .arg(
+                Arg::new("help")
+                    .short('h')
+                    .long("help")
+                    .action(ArgAction::Help),
+            )
+            .arg(Arg::new("output").short('o').long("output"));
+
+        let config = SortConfig::new().builtin_flags(BuiltinFlags::Last);
+        let plan = plan(&cmd, &config);
+
+        let moves: Vec<_> = plan[0]
+            .args
+            .iter()
+            .map(|m| (m.name.as_str(), m.from, m.to))
+            .collect();
+        assert_eq!(moves, [("-h", 0, 1), ("-o", 1, 0)]);
+    }
+
+    #[test]
+    fn test_nested_paths_and_disabled_checks() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(
+                Command::new("add")
+                    .arg(Arg::new("verbose").short('v'))
+                    .arg(Arg::new("debug").short('d')),
+            );
+
+        let plan = plan(&cmd, &SortConfig::new().subcommands(false));
+
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].path, ["test", "add"]);
+        assert_eq!(plan[0].args.len(), 2);
+    }
+}
@@ -0,0 +1,108 @@
+//! One test case per command path, for `harness = false` test targets.
+//!
+//! Splitting a tree into individual cases means failures are reported as
+//! `clap_sort::mycli::generate::task-docs`, and can be filtered and rerun on
+//! their own like any other test.
+
+use libtest_mimic::Trial;
+
+use crate::SortConfig;
+
+/// Builds a trial for every command in the tree rooted at `cmd`.
+pub(crate) fn trials(cmd: &clap::Command, config: &SortConfig) -> Vec<Trial> {
+    cases(cmd, config)
+        .into_iter()
+        .map(|(name, errors)| {
+            Trial::test(name, move || {
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors.join("\n\n").into())
+                }
+            })
+        })
+        .collect()
+}
+
+/// Names every command path and collects the errors found there.
+fn cases(cmd: &clap::Command, config: &SortConfig) -> Vec<(String, Vec<String>)> {
+    let report = crate::check_all(cmd, config);
+
+    let mut paths = Vec::new();
+    collect_paths(cmd, vec![], &mut paths);
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let errors = report
+                .errors
+                .iter()
+                .filter(|v| v.path == path)
+                .map(|v| v.message.clone())
+                .collect();
+            (format!("clap_sort::{}", path.join("::")), errors)
+        })
+        .collect()
+}
+
+/// Lists every command path in the order the tree is walked.
+fn collect_paths(cmd: &clap::Command, parent_path: Vec<String>, paths: &mut Vec<Vec<String>>) {
+    let mut current_path = parent_path;
+    current_path.push(cmd.get_name().to_string());
+
+    if !paths.contains(&current_path) {
+        paths.push(current_path.clone());
+    }
+
+    for subcmd in cmd.get_subcommands() {
+        // Multicall applets are each their own root
+        let parent_path = if cmd.is_multicall_set() {
+            vec![]
+        } else {
+            current_path.clone()
+        };
+        collect_paths(subcmd, parent_path, paths);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Command;
+
+    fn cmd() -> Command {
+        Command::new("mycli").subcommand(
+            Command::new("generate")
+                .subcommand(Command::new("task-docs"))
+                .subcommand(Command::new("completion")),
+        )
+    }
+
+    #[test]
+    fn test_one_trial_per_path() {
+        let trials = trials(&cmd(), &SortConfig::default());
+
+        let names: Vec<_> = trials.iter().map(|t| t.name()).collect();
+        assert_eq!(
+            names,
+            [
+                "clap_sort::mycli",
+                "clap_sort::mycli::generate",
+                "clap_sort::mycli::generate::task-docs",
+                "clap_sort::mycli::generate::completion",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_the_offending_path_fails() {
+        let cases = cases(&cmd(), &SortConfig::default());
+
+        let failing: Vec<_> = cases
+            .iter()
+            .filter(|(_, errors)| !errors.is_empty())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(failing, ["clap_sort::mycli::generate"]);
+    }
+}
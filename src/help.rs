@@ -0,0 +1,155 @@
+//! Validation of the order items appear in rendered `--help` output.
+//!
+//! Structural checks look at declaration order, but clap may display items
+//! differently once `display_order` or `next_display_order` come into play.
+//! This module renders the help, reads the listed subcommands and options
+//! back out, and runs them through the same ordering checks.
+
+use crate::rules::{check_argument_order, check_subcommand_order};
+use crate::{SortConfig, Violation, ViolationKind};
+
+/// Checks that the rendered help for `cmd` lists items in sorted order.
+pub(crate) fn check_rendered_help(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let mut built = cmd.clone();
+    let help = built.render_help().to_string();
+    let commands_heading = cmd.get_subcommand_help_heading().unwrap_or("Commands");
+
+    let mut found = Vec::new();
+    for (heading, entries) in sections(&help) {
+        if heading == commands_heading {
+            let names: Vec<&str> = entries
+                .iter()
+                .map(|entry| entry.trim_end_matches(','))
+                // clap appends its own help subcommand when building
+                .filter(|name| *name != "help" || cmd.find_subcommand("help").is_some())
+                .collect();
+            check_subcommand_order(names, path, config, &mut found);
+        } else {
+            let args: Vec<&clap::Arg> = entries
+                .iter()
+                .filter_map(|entry| find_flag(&built, entry))
+                .collect();
+            check_argument_order(args, path, config, &mut found);
+        }
+    }
+
+    out.extend(found.into_iter().map(|violation| Violation {
+        kind: ViolationKind::RenderedHelpOrder,
+        message: format!("Rendered help: {}", violation.message),
+        ..violation
+    }));
+}
+
+/// Splits help output into headings and the first token of each entry.
+fn sections(help: &str) -> Vec<(&str, Vec<&str>)> {
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+
+    for line in help.lines() {
+        if let Some(heading) = line.strip_suffix(':')
+            && !line.starts_with(' ')
+        {
+            sections.push((heading, Vec::new()));
+        } else if let Some((_, entries)) = sections.last_mut() {
+            // Entries start at column 2, long-only flags at column 6 so they
+            // line up with the long flags of options that have a short
+            let entry = line
+                .strip_prefix("  ")
+                .filter(|rest| !rest.starts_with(' '))
+                .or_else(|| line.strip_prefix("      --").map(|_| &line[6..]));
+            if let Some(token) = entry.and_then(|e| e.split_whitespace().next()) {
+                entries.push(token);
+            }
+        }
+    }
+
+    sections
+}
+
+/// Finds the argument a rendered flag such as `-o,` or `--config` refers to.
+fn find_flag<'a>(cmd: &'a clap::Command, token: &str) -> Option<&'a clap::Arg> {
+    let token = token.trim_end_matches(',');
+    if let Some(long) = token.strip_prefix("--") {
+        cmd.get_arguments().find(|a| a.get_long() == Some(long))
+    } else {
+        let short = token.strip_prefix('-')?.chars().next()?;
+        cmd.get_arguments().find(|a| a.get_short() == Some(short))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    #[test]
+    fn test_sections_reads_entries() {
+        let help = "\
+Usage: mycli [OPTIONS] [COMMAND]
+
+Commands:
+  list  List things [aliases: ls]
+  add   Add a thing
+
+Options:
+  -o, --output <output>  Output file
+                         that wraps
+      --config <config>
+  -h, --help             Print help
+";
+        let sections = sections(help);
+
+        assert_eq!(sections[0], ("Commands", vec!["list", "add"]));
+        assert_eq!(sections[1], ("Options", vec!["-o,", "--config", "-h,"]));
+    }
+
+    #[test]
+    fn test_display_order_caught_in_rendered_help() {
+        let cmd = Command::new("test")
+            .arg(
+                Arg::new("all")
+                    .short('a')
+                    .long("all")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .display_order(0),
+            )
+            .subcommand(Command::new("add").display_order(2))
+            .subcommand(Command::new("list").display_order(1));
+
+        let mut out = Vec::new();
+        check_rendered_help(&cmd, &["test"], &SortConfig::default(), &mut out);
+        assert_eq!(out.len(), 1, "{:?}", out);
+        assert_eq!(out[0].kind, ViolationKind::RenderedHelpOrder);
+        assert!(out[0].message.starts_with("Rendered help: "), "{}", out[0]);
+    }
+
+    #[test]
+    fn test_sorted_rendered_help() {
+        let cmd = Command::new("test")
+            .version("1.0")
+            .arg(Arg::new("file"))
+            .arg(
+                Arg::new("all")
+                    .short('a')
+                    .long("all")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("output").short('o').long("output"))
+            .arg(Arg::new("config").long("config").help_heading("Advanced"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        let mut out = Vec::new();
+        check_rendered_help(&cmd, &["test"], &SortConfig::default(), &mut out);
+        assert!(out.is_empty(), "{:?}", out);
+    }
+}
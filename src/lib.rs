@@ -8,6 +8,55 @@
 //!   1. Positional arguments (order not enforced - parsing order matters)
 //!   2. Flags with short options (alphabetically by short option)
 //!   3. Long-only flags (alphabetically)
+//!
+//! Individual checks can be turned off with [`SortConfig`].
+//!
+//! With the `toml` feature, a [`SortConfig`] can also be loaded from a file
+//! with [`SortConfig::from_path`].
+//!
+//! The panicking `assert_*` functions are behind the default `assert`
+//! feature. Build with `default-features = false` to guarantee that only the
+//! `Result`-returning functions are available.
+
+mod assembly;
+mod cache;
+mod codegen;
+mod config;
+#[cfg(feature = "toml")]
+mod config_file;
+mod diff;
+mod display_order;
+mod ext;
+mod fix;
+#[cfg(feature = "libtest-mimic")]
+mod harness;
+mod help;
+#[cfg(feature = "inventory")]
+mod registry;
+mod report;
+mod rules;
+mod sort;
+mod violation;
+
+pub use assembly::Assembly;
+pub use cache::CheckCache;
+pub use config::{
+    BuiltinFlags, Direction, GroupBy, NonAlphaShorts, ShortKey, SortConfig, ValueNameStyle,
+    Verbosity,
+};
+pub use ext::CommandExt;
+#[cfg(feature = "assert")]
+pub use ext::ParserExt;
+pub use fix::{CommandFix, ExpectedOrder, FixPlan, Move};
+#[cfg(feature = "inventory")]
+#[doc(hidden)]
+pub use registry::{__private, Registration};
+pub use report::Report;
+pub use violation::{Severity, Violation, ViolationKind};
+
+use std::collections::{BTreeMap, HashMap};
+
+use report::Collector;
 
 /// Validates that subcommands and arguments are sorted correctly.
 ///
@@ -35,38 +84,111 @@
 ///
 /// clap_sort::assert_sorted(&cmd);
 /// ```
+#[cfg(feature = "assert")]
+#[track_caller]
 pub fn assert_sorted(cmd: &clap::Command) {
-    assert_sorted_with_path(cmd, vec![]);
+    if let Err(msg) = is_sorted(cmd) {
+        fail(msg);
+    }
 }
 
-fn assert_sorted_with_path(cmd: &clap::Command, parent_path: Vec<&str>) {
-    let mut current_path = parent_path.clone();
-    current_path.push(cmd.get_name());
-
-    // Check subcommands
-    let subcommands: Vec<_> = cmd.get_subcommands().map(|s| s.get_name()).collect();
-
-    if !subcommands.is_empty() {
-        let mut sorted = subcommands.clone();
-        sorted.sort();
+/// Validates that the command tree below `path` is sorted, using `config`.
+///
+/// `path` names subcommands starting below the root command, so
+/// `&["generate", "task-docs"]` validates `mycli generate task-docs` and
+/// everything beneath it. An empty path validates the whole tree. Error
+/// messages still show the full command path from the root.
+///
+/// # Panics
+/// Panics if `path` does not exist, or if the subtree is not properly sorted.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("zebra"))
+///     .subcommand(
+///         Command::new("generate")
+///             .arg(Arg::new("output").short('o').long("output"))
+///             .arg(Arg::new("style").short('s').long("style")),
+///     );
+///
+/// // The root subcommands are unsorted, but only `generate` is checked
+/// clap_sort::assert_sorted_at(&cmd, &["generate"], &SortConfig::default());
+/// ```
+#[cfg(feature = "assert")]
+#[track_caller]
+pub fn assert_sorted_at(cmd: &clap::Command, path: &[&str], config: &SortConfig) {
+    if let Err(msg) = is_sorted_at_with_config(cmd, path, config) {
+        fail(msg);
+    }
+}
 
-        if subcommands != sorted {
-            panic!(
-                "Subcommands in '{}' are not sorted alphabetically!\nActual order: {:?}\nExpected order: {:?}",
-                current_path.join(" "),
-                subcommands,
-                sorted
-            );
-        }
+/// Like [`assert_sorted`], but builds the command from a `CommandFactory`
+/// such as a `#[derive(Parser)]` struct.
+///
+/// # Panics
+/// Panics if subcommands or arguments are not properly sorted.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+///
+/// #[derive(Parser)]
+/// struct Cli {
+///     #[arg(short, long)]
+///     debug: bool,
+///     #[arg(short, long)]
+///     verbose: bool,
+/// }
+///
+/// clap_sort::assert_sorted_factory::<Cli>();
+/// ```
+#[cfg(feature = "assert")]
+#[track_caller]
+pub fn assert_sorted_factory<T: clap::CommandFactory>() {
+    if let Err(msg) = is_sorted_factory::<T>() {
+        fail(msg);
     }
+}
 
-    // Check arguments
-    assert_arguments_sorted_with_path(cmd, &current_path);
+/// Like [`assert_sorted`], but only checks in builds with debug assertions.
+///
+/// Meant for calling from `main()` while developing: release builds skip
+/// the validation entirely, so they neither pay for it nor panic.
+///
+/// # Panics
+/// With debug assertions, panics if subcommands or arguments are not
+/// properly sorted.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("add"))
+///     .subcommand(Command::new("list"));
+///
+/// clap_sort::debug_assert_sorted(&cmd);
+/// ```
+#[cfg(feature = "assert")]
+#[track_caller]
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub fn debug_assert_sorted(cmd: &clap::Command) {
+    #[cfg(debug_assertions)]
+    assert_sorted(cmd);
+}
 
-    // Recursively check subcommands
-    for subcmd in cmd.get_subcommands() {
-        assert_sorted_with_path(subcmd, current_path.clone());
-    }
+/// Panics with `msg`, naming the caller of the public assertion.
+#[cfg(feature = "assert")]
+#[track_caller]
+fn fail(msg: String) -> ! {
+    panic!("{}\nChecked at: {}", msg, std::panic::Location::caller());
 }
 
 /// Checks if subcommands and arguments are sorted, returning a Result instead of panicking.
@@ -89,181 +211,695 @@ fn assert_sorted_with_path(cmd: &clap::Command, parent_path: Vec<&str>) {
 /// }
 /// ```
 pub fn is_sorted(cmd: &clap::Command) -> Result<(), String> {
-    is_sorted_with_path(cmd, vec![])
+    is_sorted_at_with_config(cmd, &[], &SortConfig::default())
 }
 
-fn is_sorted_with_path(cmd: &clap::Command, parent_path: Vec<&str>) -> Result<(), String> {
-    let mut current_path = parent_path.clone();
-    current_path.push(cmd.get_name());
+/// Checks if the command tree is sorted according to `config`, returning a
+/// Result instead of panicking.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .arg(Arg::new("verbose").short('v').long("verbose"))
+///     .arg(Arg::new("debug").short('d').long("debug"));
+///
+/// let config = SortConfig::new().short_flags(false);
+/// assert!(clap_sort::is_sorted_with_config(&cmd, &config).is_ok());
+/// ```
+pub fn is_sorted_with_config(cmd: &clap::Command, config: &SortConfig) -> Result<(), String> {
+    is_sorted_at_with_config(cmd, &[], config)
+}
 
-    // Check subcommands
-    let subcommands: Vec<_> = cmd.get_subcommands().map(|s| s.get_name()).collect();
+/// Checks if the command tree below `path` is sorted, returning a Result
+/// instead of panicking.
+///
+/// `path` names subcommands starting below the root command, as with
+/// [`assert_sorted_at`]. Errors still show the full command path, and a
+/// missing path is an error.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("zebra"))
+///     .subcommand(
+///         Command::new("generate")
+///             .subcommand(Command::new("docs"))
+///             .subcommand(Command::new("task-docs")),
+///     );
+///
+/// // The root subcommands are unsorted, but only `generate` is checked
+/// assert!(clap_sort::is_sorted_at(&cmd, &["generate"]).is_ok());
+/// ```
+pub fn is_sorted_at(cmd: &clap::Command, path: &[&str]) -> Result<(), String> {
+    is_sorted_at_with_config(cmd, path, &SortConfig::default())
+}
 
-    if !subcommands.is_empty() {
-        let mut sorted = subcommands.clone();
-        sorted.sort();
+/// Like [`is_sorted`], but builds the command from a `CommandFactory` such
+/// as a `#[derive(Parser)]` struct.
+pub fn is_sorted_factory<T: clap::CommandFactory>() -> Result<(), String> {
+    is_sorted(&T::command())
+}
 
-        if subcommands != sorted {
-            return Err(format!(
-                "Subcommands in '{}' are not sorted alphabetically!\nActual order: {:?}\nExpected order: {:?}",
-                current_path.join(" "),
-                subcommands,
-                sorted
-            ));
-        }
-    }
+/// Collects every violation in the command tree, split into errors and
+/// warnings according to the severities in `config`.
+///
+/// Unlike [`is_sorted`], this does not stop at the first problem, so a
+/// single run shows everything that needs fixing.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .arg(Arg::new("verbose").short('v').long("verbose"))
+///     .arg(Arg::new("debug").short('d').long("debug"))
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let report = clap_sort::check_all(&cmd, &SortConfig::default());
+/// assert_eq!(report.errors.len(), 2);
+/// ```
+pub fn check_all(cmd: &clap::Command, config: &SortConfig) -> Report {
+    let mut collected = Collector::new(config.max_violations);
+    check_tree(cmd, vec![], config, None, &mut collected);
+    Report::from_collector(collected, config)
+}
 
-    // Check arguments
-    is_arguments_sorted_with_path(cmd, &current_path)?;
+/// Like [`check_all`], but reuses results for subtrees that `cache` has
+/// already seen.
+///
+/// Useful when many tests validate the same large `Command`, or when
+/// property tests mutate one branch of it at a time.
+pub fn check_all_cached(
+    cmd: &clap::Command,
+    config: &SortConfig,
+    cache: &mut CheckCache,
+) -> Report {
+    cache.use_config(config);
+    let mut collected = Collector::new(config.max_violations);
+    check_tree(cmd, vec![], config, Some(cache), &mut collected);
+    Report::from_collector(collected, config)
+}
 
-    // Recursively check subcommands
-    for subcmd in cmd.get_subcommands() {
-        is_sorted_with_path(subcmd, current_path.clone())?;
+/// Runs [`check_all`] on several CLIs at once, keyed by command name.
+///
+/// Meant for workspaces with several binaries, so one test covers them
+/// all. Commands sharing a name are merged into one report.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::SortConfig;
+///
+/// let server = Command::new("server").subcommand(Command::new("start"));
+/// let client = Command::new("client")
+///     .subcommand(Command::new("push"))
+///     .subcommand(Command::new("pull"));
+///
+/// let reports = clap_sort::check_commands([&server, &client], &SortConfig::default());
+///
+/// assert!(reports["server"].is_ok());
+/// assert_eq!(reports["client"].errors.len(), 1);
+/// ```
+pub fn check_commands<'a>(
+    cmds: impl IntoIterator<Item = &'a clap::Command>,
+    config: &SortConfig,
+) -> BTreeMap<String, Report> {
+    let mut reports: BTreeMap<String, Report> = BTreeMap::new();
+    for cmd in cmds {
+        let report = check_all(cmd, config);
+        let merged = reports.entry(cmd.get_name().to_string()).or_default();
+        merged.errors.extend(report.errors);
+        merged.warnings.extend(report.warnings);
+        merged.omitted += report.omitted;
+        merged.config_files = report.config_files;
     }
+    reports
+}
+
+/// Runs [`check_all`] on every command registered with [`register!`],
+/// keyed by command name like [`check_commands`].
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+/// use clap_sort::SortConfig;
+///
+/// #[derive(Parser)]
+/// #[command(name = "mycli")]
+/// struct Cli {
+///     #[arg(short, long)]
+///     verbose: bool,
+/// }
+///
+/// clap_sort::register!(Cli);
+///
+/// let reports = clap_sort::validate_registered(&SortConfig::default());
+/// assert!(reports.values().all(|report| report.is_ok()));
+/// ```
+#[cfg(feature = "inventory")]
+pub fn validate_registered(config: &SortConfig) -> BTreeMap<String, Report> {
+    check_commands(&registry::registered_commands(), config)
+}
 
-    Ok(())
+/// Expands the tree rooted at `cmd` into one `libtest-mimic` test case per
+/// command path, named like `clap_sort::mycli::generate::task-docs`.
+///
+/// Each case fails with the errors found at its path, so failures can be
+/// filtered and rerun individually. Use it from a test target with
+/// `harness = false`:
+///
+/// ```rust,no_run
+/// use clap::Command;
+/// use clap_sort::SortConfig;
+///
+/// # fn build_cli() -> Command { Command::new("mycli") }
+/// fn main() {
+///     let args = libtest_mimic::Arguments::from_args();
+///     let trials = clap_sort::trials(&build_cli(), &SortConfig::default());
+///     libtest_mimic::run(&args, trials).exit();
+/// }
+/// ```
+#[cfg(feature = "libtest-mimic")]
+pub fn trials(cmd: &clap::Command, config: &SortConfig) -> Vec<libtest_mimic::Trial> {
+    harness::trials(cmd, config)
 }
 
-/// Internal function to assert arguments are sorted.
-fn assert_arguments_sorted_with_path(cmd: &clap::Command, path: &[&str]) {
-    if let Err(msg) = is_arguments_sorted_with_path(cmd, path) {
-        panic!("{}", msg);
+/// Runs [`check_all`] and logs every violation as a `warn!` record instead
+/// of failing.
+///
+/// Meant for services that embed a CLI and want sorting issues to show up
+/// in their telemetry while a migration is under way. Records use the
+/// `clap_sort` target; the report is returned for callers that want more.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// // Logged, but doesn't panic
+/// let report = clap_sort::check_and_log(&cmd, &SortConfig::default());
+/// assert_eq!(report.errors.len(), 1);
+/// ```
+#[cfg(feature = "log")]
+pub fn check_and_log(cmd: &clap::Command, config: &SortConfig) -> Report {
+    let report = check_all(cmd, config);
+    for violation in report.errors.iter().chain(&report.warnings) {
+        log::warn!(target: "clap_sort", "{}", violation);
+    }
+    if report.omitted > 0 {
+        log::warn!(
+            target: "clap_sort",
+            "{} more violation(s) in '{}' not shown",
+            report.omitted,
+            cmd.get_name()
+        );
     }
+    report
 }
 
-/// Checks if arguments are sorted correctly, returning a Result.
-fn is_arguments_sorted_with_path(cmd: &clap::Command, path: &[&str]) -> Result<(), String> {
-    let args: Vec<_> = cmd.get_arguments().collect();
+/// Checks the command of `T` against the project's configuration.
+///
+/// This is the one-line test most projects need: the configuration comes
+/// from [`SortConfig::discover`], and every enabled rule is run. It fails
+/// only if the config file can't be loaded or doesn't fit the command, as
+/// checked by [`SortConfig::validate`].
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+///
+/// #[derive(Parser)]
+/// struct Cli {
+///     #[arg(short, long)]
+///     verbose: bool,
+/// }
+///
+/// let report = clap_sort::check::<Cli>().unwrap();
+/// assert!(report.is_ok(), "{:#?}", report.errors);
+/// ```
+pub fn check<T: clap::CommandFactory>() -> Result<Report, String> {
+    check_validated(&T::command(), &SortConfig::discover()?)
+}
 
-    let mut positional = Vec::new();
-    let mut with_short = Vec::new();
-    let mut long_only = Vec::new();
+/// Validates `config` against `cmd`, then runs every enabled rule.
+fn check_validated(cmd: &clap::Command, config: &SortConfig) -> Result<Report, String> {
+    config.validate(cmd)?;
+    Ok(check_all(cmd, config))
+}
 
-    for arg in &args {
-        if arg.is_positional() {
-            positional.push(*arg);
-        } else if arg.get_short().is_some() {
-            with_short.push(*arg);
-        } else if arg.get_long().is_some() {
-            long_only.push(*arg);
-        }
-    }
+/// Calls `f` with every command in the tree rooted at `cmd`, parents
+/// before their subcommands.
+///
+/// The path passed along starts with the root command and ends with the
+/// command itself, the same form as [`Violation::path`]. Multicall applets
+/// are each their own root. Useful for project-specific checks that need
+/// the same traversal as the built-in rules.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("generate").subcommand(Command::new("task-docs")));
+///
+/// let mut paths = Vec::new();
+/// clap_sort::walk(&cmd, |path, _| paths.push(path.join(" ")));
+///
+/// assert_eq!(paths, ["mycli", "mycli generate", "mycli generate task-docs"]);
+/// ```
+pub fn walk<'a>(cmd: &'a clap::Command, mut f: impl FnMut(&[&'a str], &'a clap::Command)) {
+    walk_tree(cmd, vec![], &mut f);
+}
 
-    // Note: We don't check if positional args are sorted - their order matters for parsing
-
-    // Check short flags are sorted by short option
-    let with_short_shorts: Vec<char> = with_short.iter().filter_map(|a| a.get_short()).collect();
-    let mut sorted_shorts = with_short_shorts.clone();
-    sorted_shorts.sort_by(|a, b| {
-        let a_lower = a.to_ascii_lowercase();
-        let b_lower = b.to_ascii_lowercase();
-        match a_lower.cmp(&b_lower) {
-            std::cmp::Ordering::Equal => {
-                // Lowercase before uppercase for same letter
-                if a.is_lowercase() && b.is_uppercase() {
-                    std::cmp::Ordering::Less
-                } else if a.is_uppercase() && b.is_lowercase() {
-                    std::cmp::Ordering::Greater
-                } else {
-                    std::cmp::Ordering::Equal
-                }
-            }
-            other => other,
-        }
-    });
+/// Describes the reorderings that would make the command tree sorted.
+///
+/// Each [`CommandFix`] lists, for one command path, the current and target
+/// index of every subcommand and argument that has to move. Tools that
+/// rewrite CLI definitions can apply the plan instead of parsing messages.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let plan = clap_sort::fix_plan(&cmd, &SortConfig::default());
+/// let moves = &plan.commands[0].subcommands;
+///
+/// assert_eq!((moves[0].name.as_str(), moves[0].from, moves[0].to), ("list", 0, 1));
+/// assert_eq!((moves[1].name.as_str(), moves[1].from, moves[1].to), ("add", 1, 0));
+/// ```
+pub fn fix_plan(cmd: &clap::Command, config: &SortConfig) -> FixPlan {
+    let mut commands = Vec::new();
+    fix::plan_command(cmd, vec![], false, config, &mut commands);
+    FixPlan { commands }
+}
 
-    if with_short_shorts != sorted_shorts {
-        let current: Vec<String> = with_short
-            .iter()
-            .map(|a| format!("-{}", a.get_short().unwrap()))
-            .collect();
-        let mut sorted_args = with_short.clone();
-        sorted_args.sort_by(|a, b| {
-            let a_char = a.get_short().unwrap();
-            let b_char = b.get_short().unwrap();
-            let a_lower = a_char.to_ascii_lowercase();
-            let b_lower = b_char.to_ascii_lowercase();
-            match a_lower.cmp(&b_lower) {
-                std::cmp::Ordering::Equal => {
-                    if a_char.is_lowercase() && b_char.is_uppercase() {
-                        std::cmp::Ordering::Less
-                    } else if a_char.is_uppercase() && b_char.is_lowercase() {
-                        std::cmp::Ordering::Greater
-                    } else {
-                        std::cmp::Ordering::Equal
-                    }
-                }
-                other => other,
-            }
-        });
-        let expected: Vec<String> = sorted_args
-            .iter()
-            .map(|a| format!("-{}", a.get_short().unwrap()))
-            .collect();
+/// Returns the order `config` expects for the subcommand at `path` below
+/// `cmd`; an empty path means `cmd` itself.
+///
+/// Subcommands come out in their expected order, and arguments grouped the
+/// way they are checked. Meant for tools that show what a command should
+/// look like rather than what is wrong with it. Fails if `path` doesn't
+/// exist.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli")
+///     .arg(Arg::new("verbose").short('v').long("verbose"))
+///     .arg(Arg::new("debug").short('d').long("debug"))
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let expected = clap_sort::expected_order(&cmd, &[], &SortConfig::default()).unwrap();
+///
+/// assert_eq!(expected.subcommands, ["add", "list"]);
+/// assert_eq!(expected.short_flags, ["-d", "-v"]);
+/// ```
+pub fn expected_order(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+) -> Result<ExpectedOrder, String> {
+    let (current, mut current_path) = find_subcommand(cmd, path)?;
+    let config = config.for_depth(current_path.len());
+    current_path.push(current.get_name());
+    Ok(fix::expected_order(current, &current_path, config))
+}
 
-        return Err(format!(
-            "Flags with short options in '{}' are not sorted!\nActual: {:?}\nExpected: {:?}",
-            path.join(" "),
-            current,
-            expected
-        ));
-    }
+/// Makes the help output of `cmd` list its subcommands and arguments sorted.
+///
+/// This is the runtime alternative to sorting the definitions by hand: each
+/// item gets the `display_order` of its sorted position, recursively. The
+/// items are also moved into that order, so `get_subcommands()` and
+/// `get_arguments()` return them sorted and [`is_sorted`] passes.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let mut cmd = clap_sort::sort_command(cmd);
+/// let help = cmd.render_help().to_string();
+/// assert!(help.find("add").unwrap() < help.find("list").unwrap());
+/// ```
+pub fn sort_command(cmd: clap::Command) -> clap::Command {
+    sort::sort_command(cmd, &SortConfig::default())
+}
 
-    // Check long-only flags are sorted
-    let long_only_longs: Vec<&str> = long_only.iter().filter_map(|a| a.get_long()).collect();
-    let mut sorted_longs = long_only_longs.clone();
-    sorted_longs.sort_unstable();
+/// Like [`sort_command`], but only sorts subcommands.
+///
+/// Useful when argument order is managed by hand.
+pub fn sort_subcommands(cmd: clap::Command) -> clap::Command {
+    sort::sort_subcommands(cmd, &SortConfig::default())
+}
 
-    if long_only_longs != sorted_longs {
-        let current: Vec<String> = long_only_longs.iter().map(|l| format!("--{}", l)).collect();
-        let expected: Vec<String> = sorted_longs.iter().map(|l| format!("--{}", l)).collect();
+/// Like [`sort_command`], but only sorts arguments.
+///
+/// Useful when subcommand order is managed by hand.
+pub fn sort_args(cmd: clap::Command) -> clap::Command {
+    sort::sort_args(cmd, &SortConfig::default())
+}
 
-        return Err(format!(
-            "Long-only flags in '{}' are not sorted!\nActual: {:?}\nExpected: {:?}",
-            path.join(" "),
-            current,
-            expected
-        ));
-    }
+/// Generates the source of a `#[derive(Subcommand)]` enum named `name` that
+/// is equivalent to the subcommands of `cmd`, with variants in sorted order.
+///
+/// Nested command groups get their own enums (e.g. `ConfigCommands`),
+/// appended after the first. Arguments become fields typed from their
+/// action; values are `String`s since the parsed type isn't recoverable at
+/// runtime. The output expects `clap::Subcommand` to be in scope.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let source = clap_sort::to_subcommand_enum(&cmd, "Commands");
+/// assert_eq!(source, "#[derive(Subcommand)]\npub enum Commands {\n    Add,\n    List,\n}\n");
+/// ```
+pub fn to_subcommand_enum(cmd: &clap::Command, name: &str) -> String {
+    codegen::subcommand_enum(cmd, name, &SortConfig::default())
+}
 
-    // Skip group order checking when flattened structs are involved
-    // Flattened structs can cause positionals and flags to be interspersed,
-    // which is valid for clap but would fail a strict group order check.
-    // We only care that within each group (positionals, short flags, long-only flags),
-    // the items are sorted correctly.
+/// Generates builder-API source that recreates `cmd`, with arguments and
+/// subcommands in sorted order.
+///
+/// Arguments are grouped into positionals, short flags and long-only flags.
+/// The output is a single `Command::new(..)` expression with one method call
+/// per line. It covers names, aliases, help text, flags, defaults and
+/// actions; other settings have to be carried over by hand. Help and version
+/// flags are left to clap to generate. The output
+/// expects `Arg`, `ArgAction` and `Command` to be in scope.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// assert_eq!(
+///     clap_sort::to_builder_source(&cmd),
+///     "Command::new(\"mycli\")\n    .subcommand(Command::new(\"add\"))\n    .subcommand(Command::new(\"list\"))"
+/// );
+/// ```
+pub fn to_builder_source(cmd: &clap::Command) -> String {
+    codegen::builder_source(cmd, &SortConfig::default())
+}
 
-    Ok(())
+/// Like [`is_sorted_at`], but checks according to `config`.
+///
+/// The other `is_sorted*` and `assert_sorted*` functions all end up here: it
+/// descends to `path` and validates the subtree found there.
+pub fn is_sorted_at_with_config(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+) -> Result<(), String> {
+    let (current, parent_path) = find_subcommand(cmd, path)?;
+
+    let mut collected = Collector::new(None);
+    check_tree(current, parent_path.clone(), config, None, &mut collected);
+
+    let Some(violation) = collected
+        .violations
+        .into_iter()
+        .find(|v| v.severity == Severity::Error)
+    else {
+        return Ok(());
+    };
+
+    let mut msg = violation.message;
+    if config.full_listing
+        && let Some(listing) = fix::expected_listing(current, parent_path, &violation.path, config)
+    {
+        msg.push_str("\n\n");
+        msg.push_str(&listing);
+    }
+    Err(msg)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use clap::{Command, CommandFactory, Parser, Subcommand};
+/// Finds the subcommand at `path` below `cmd`, along with the path of its
+/// parent.
+fn find_subcommand<'a>(
+    cmd: &'a clap::Command,
+    path: &[&str],
+) -> Result<(&'a clap::Command, Vec<&'a str>), String> {
+    let mut current = cmd;
+    let mut parent_path = vec![];
+
+    for name in path {
+        parent_path.push(current.get_name());
+        current = current.find_subcommand(name).ok_or_else(|| {
+            format!(
+                "Subcommand '{}' not found in '{}'",
+                name,
+                parent_path.join(" ")
+            )
+        })?;
+    }
 
-    #[test]
-    fn test_sorted_subcommands() {
-        let cmd = Command::new("test")
-            .subcommand(Command::new("add"))
-            .subcommand(Command::new("delete"))
-            .subcommand(Command::new("list"));
+    Ok((current, parent_path))
+}
 
-        assert_sorted(&cmd);
+/// Runs every check on the tree rooted at `cmd`.
+///
+/// Violations come out depth-first by command path, in the order commands
+/// are declared, and by [`ViolationKind`] within a command. The order only
+/// depends on the tree, so reports are stable across runs and platforms.
+fn check_tree<'a>(
+    cmd: &'a clap::Command,
+    parent_path: Vec<&'a str>,
+    config: &SortConfig,
+    cache: Option<&mut CheckCache>,
+    out: &mut Collector,
+) {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("check", root = cmd.get_name()).entered();
+    #[cfg(feature = "tracing")]
+    let started = std::time::Instant::now();
+
+    let start = out.violations.len();
+    check_command_recursive(cmd, parent_path.clone(), false, config, cache, out);
+
+    // Checks that compare commands with each other
+    if config.display_order_consistency {
+        let mut found = Vec::new();
+        display_order::check_display_order(cmd, parent_path.clone(), &mut found);
+        config.apply_severities(&mut found, 0);
+        found.retain(|v| !config.is_ignored_path(&v.path[1..]));
+        out.extend(found);
     }
 
-    #[test]
-    #[should_panic(expected = "are not sorted alphabetically")]
-    fn test_unsorted_subcommands() {
-        let cmd = Command::new("test")
-            .subcommand(Command::new("list"))
-            .subcommand(Command::new("add"))
-            .subcommand(Command::new("delete"));
+    let mut paths = HashMap::new();
+    visit_paths(cmd, parent_path, &mut paths);
+    out.violations[start..].sort_by_key(|v| (paths.get(&v.path).copied(), v.kind));
 
-        assert_sorted(&cmd);
-    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        violations = out.violations.len() - start,
+        elapsed_us = started.elapsed().as_micros() as u64,
+        "check finished"
+    );
+}
 
-    #[test]
-    fn test_is_sorted_ok() {
-        let cmd = Command::new("test")
+/// Numbers every command path in the order the tree is walked.
+fn visit_paths(
+    cmd: &clap::Command,
+    parent_path: Vec<&str>,
+    paths: &mut HashMap<Vec<String>, usize>,
+) {
+    walk_tree(cmd, parent_path, &mut |path, _| {
+        let index = paths.len();
+        paths
+            .entry(path.iter().map(|p| p.to_string()).collect())
+            .or_insert(index);
+    });
+}
+
+fn walk_tree<'a>(
+    cmd: &'a clap::Command,
+    parent_path: Vec<&'a str>,
+    f: &mut impl FnMut(&[&'a str], &'a clap::Command),
+) {
+    let mut current_path = parent_path;
+    current_path.push(cmd.get_name());
+    f(&current_path, cmd);
+
+    // Multicall applets are each their own root
+    for subcmd in cmd.get_subcommands() {
+        let parent_path = if cmd.is_multicall_set() {
+            vec![]
+        } else {
+            current_path.clone()
+        };
+        walk_tree(subcmd, parent_path, f);
+    }
+}
+
+/// Returns the part of `path` that config paths are matched against: the
+/// commands below the root, or, inside a multicall applet, the applet and
+/// the commands below it.
+pub(crate) fn config_path<'p, 'a>(path: &'p [&'a str], in_applet: bool) -> &'p [&'a str] {
+    if in_applet { path } else { &path[1..] }
+}
+
+/// `in_applet` is set below a multicall command, where `parent_path` starts
+/// at the applet rather than the root.
+fn check_command_recursive<'a>(
+    cmd: &'a clap::Command,
+    parent_path: Vec<&'a str>,
+    in_applet: bool,
+    config: &SortConfig,
+    mut cache: Option<&mut CheckCache>,
+    out: &mut Collector,
+) {
+    let key = cache
+        .as_ref()
+        .map(|cache| cache.key(&parent_path, in_applet, cmd));
+    if let (Some(cache), Some(key)) = (cache.as_deref_mut(), key)
+        && let Some(found) = cache.get(key)
+    {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(command = cmd.get_name(), "subtree cached");
+        out.extend(found.iter().cloned());
+        return;
+    }
+
+    // The cache remembers whole subtrees, so they are collected uncapped
+    if cache.is_some() && out.is_capped() {
+        let mut all = Collector::new(None);
+        check_command_recursive(cmd, parent_path, in_applet, config, cache, &mut all);
+        out.extend(all.violations);
+        return;
+    }
+
+    let mut current_path = parent_path.clone();
+    current_path.push(cmd.get_name());
+    if config.is_ignored_path(config_path(&current_path, in_applet))
+        || config.is_skipped_subcommand(cmd)
+        || config.is_too_deep(parent_path.len())
+    {
+        return;
+    }
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("command", path = %current_path.join(" ")).entered();
+
+    let root_config = config;
+    let config = root_config.for_depth(parent_path.len());
+
+    let start = out.violations.len();
+    let mut found = Vec::new();
+    rules::check_command(cmd, &current_path, config, &mut found);
+    config.apply_severities(&mut found, 0);
+    out.extend(found);
+
+    // Recursively check subcommands; multicall applets are each their own root
+    for subcmd in cmd.get_subcommands() {
+        let parent_path = if cmd.is_multicall_set() {
+            vec![]
+        } else {
+            current_path.clone()
+        };
+        check_command_recursive(
+            subcmd,
+            parent_path,
+            in_applet || cmd.is_multicall_set(),
+            root_config,
+            cache.as_deref_mut(),
+            out,
+        );
+    }
+
+    if let (Some(cache), Some(key)) = (cache, key) {
+        cache.insert(key, &out.violations[start..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Command, CommandFactory, Parser, Subcommand};
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_sorted_subcommands() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("delete"))
+            .subcommand(Command::new("list"));
+
+        assert_sorted(&cmd);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "are not sorted alphabetically")]
+    fn test_unsorted_subcommands() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("delete"));
+
+        assert_sorted(&cmd);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic(expected = "are not sorted alphabetically")
+    )]
+    fn test_debug_assert_sorted() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"));
+
+        // Only panics when tests are built with debug assertions
+        debug_assert_sorted(&cmd);
+    }
+
+    #[test]
+    fn test_is_sorted_ok() {
+        let cmd = Command::new("test")
             .subcommand(Command::new("add"))
             .subcommand(Command::new("delete"))
             .subcommand(Command::new("list"));
@@ -280,6 +916,7 @@ mod tests {
         assert!(is_sorted(&cmd).is_err());
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_no_subcommands() {
         let cmd = Command::new("test");
@@ -287,6 +924,7 @@ mod tests {
         assert!(is_sorted(&cmd).is_ok());
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_with_derive_sorted() {
         #[derive(Parser)]
@@ -306,6 +944,75 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[test]
+    fn test_check_derive() {
+        #[derive(Parser)]
+        struct Cli {
+            #[command(subcommand)]
+            command: Commands,
+        }
+
+        #[derive(Subcommand)]
+        enum Commands {
+            List,
+            Add,
+        }
+
+        // The config `check` discovers, with fixed overrides instead of
+        // the environment's
+        let config =
+            |vars: fn(&str) -> Option<String>| SortConfig::new().with_overrides(vars).unwrap();
+
+        let report = check_validated(&Cli::command(), &config(|_| None)).unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::UnsortedSubcommands);
+
+        let disabled = config(|name| {
+            (name == "CLAP_SORT_DISABLE").then(|| "unsorted-subcommands".to_string())
+        });
+        assert!(check_validated(&Cli::command(), &disabled).unwrap().is_ok());
+
+        let err =
+            check_validated(&Cli::command(), &config(|_| None).ignore_path(&["lsit"])).unwrap_err();
+        assert_eq!(
+            err,
+            "Path 'lsit' in 'ignore-paths' doesn't match any command in 'clap-sort'"
+        );
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_factory_helpers() {
+        #[derive(Parser)]
+        struct Sorted {
+            #[command(subcommand)]
+            command: SortedCommands,
+        }
+
+        #[derive(Subcommand)]
+        enum SortedCommands {
+            Add,
+            List,
+        }
+
+        #[derive(Parser)]
+        struct Unsorted {
+            #[command(subcommand)]
+            command: UnsortedCommands,
+        }
+
+        #[derive(Subcommand)]
+        enum UnsortedCommands {
+            List,
+            Add,
+        }
+
+        assert_sorted_factory::<Sorted>();
+        assert!(is_sorted_factory::<Sorted>().is_ok());
+        assert!(is_sorted_factory::<Unsorted>().is_err());
+    }
+
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "are not sorted alphabetically")]
     fn test_with_derive_unsorted() {
@@ -328,6 +1035,7 @@ mod tests {
 
     // Tests for argument sorting
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_arguments_correctly_sorted() {
         use clap::{Arg, ArgAction};
@@ -357,6 +1065,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "Flags with short options")]
     fn test_short_flags_unsorted() {
@@ -369,6 +1078,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "Long-only flags")]
     fn test_long_only_unsorted() {
@@ -381,6 +1091,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_positional_order_not_enforced() {
         // Positional arguments can be in any order since their order matters for parsing
@@ -416,6 +1127,7 @@ mod tests {
         assert!(is_sorted(&cmd).is_err());
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_recursive_subcommand_args() {
         use clap::{Arg, ArgAction};
@@ -441,6 +1153,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "Flags with short options")]
     fn test_recursive_subcommand_args_fails() {
@@ -455,6 +1168,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_global_flags_not_checked_in_subcommands() {
         use clap::{Arg, ArgAction};
@@ -483,6 +1197,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_global_flags_dont_appear_in_subcommand_args() {
         use clap::{Arg, ArgAction};
@@ -527,6 +1242,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "Flags with short options")]
     fn test_uppercase_before_lowercase_same_letter() {
@@ -540,6 +1256,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     fn test_lowercase_before_uppercase_same_letter() {
         use clap::Arg;
@@ -552,6 +1269,7 @@ mod tests {
         assert_sorted(&cmd);
     }
 
+    #[cfg(feature = "assert")]
     #[test]
     #[should_panic(expected = "Flags with short options")]
     fn test_task_docs_flags_unsorted() {
@@ -657,4 +1375,1688 @@ mod tests {
             panic!("Expected error for unsorted flags");
         }
     }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_assert_sorted_at_ignores_rest_of_tree() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli")
+            .subcommand(Command::new("zebra"))
+            .subcommand(
+                Command::new("generate")
+                    .arg(Arg::new("output").short('o').long("output"))
+                    .arg(Arg::new("style").short('s').long("style")),
+            );
+
+        assert_sorted_at(&cmd, &["generate"], &SortConfig::default());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "in 'mycli generate task-docs' are not sorted")]
+    fn test_assert_sorted_at_reports_full_path() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("generate").subcommand(
+                Command::new("task-docs")
+                    .arg(Arg::new("style").short('s').long("style"))
+                    .arg(Arg::new("output").short('o').long("output")),
+            ),
+        );
+
+        assert_sorted_at(&cmd, &["generate", "task-docs"], &SortConfig::default());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "Subcommand 'missing' not found in 'mycli generate'")]
+    fn test_assert_sorted_at_missing_path() {
+        let cmd = Command::new("mycli").subcommand(Command::new("generate"));
+
+        assert_sorted_at(&cmd, &["generate", "missing"], &SortConfig::default());
+    }
+
+    #[test]
+    fn test_expected_order() {
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("generate")
+                .arg(clap::Arg::new("name"))
+                .arg(clap::Arg::new("verbose").short('v').long("verbose"))
+                .arg(clap::Arg::new("force").long("force"))
+                .arg(clap::Arg::new("all").short('a').long("all"))
+                .subcommand(Command::new("task-docs"))
+                .subcommand(Command::new("completion")),
+        );
+        let config = SortConfig::default();
+
+        let expected = expected_order(&cmd, &["generate"], &config).unwrap();
+        assert_eq!(expected.path, ["mycli", "generate"]);
+        assert_eq!(expected.subcommands, ["completion", "task-docs"]);
+        assert_eq!(expected.positionals, ["name"]);
+        assert_eq!(expected.short_flags, ["-a", "-v"]);
+        assert_eq!(expected.long_flags, ["--force"]);
+        assert!(expected.builtin_flags.is_empty());
+
+        let err = expected_order(&cmd, &["missing"], &config).unwrap_err();
+        assert_eq!(err, "Subcommand 'missing' not found in 'mycli'");
+    }
+
+    #[test]
+    fn test_walk() {
+        let cmd = Command::new("busybox")
+            .multicall(true)
+            .subcommand(Command::new("ls").subcommand(Command::new("tree").hide(true)))
+            .subcommand(Command::new("cat"));
+
+        let mut visited = Vec::new();
+        walk(&cmd, |path, cmd| {
+            visited.push((path.join(" "), cmd.is_hide_set()));
+        });
+
+        assert_eq!(
+            visited,
+            [
+                ("busybox".to_string(), false),
+                ("ls".to_string(), false),
+                ("ls tree".to_string(), true),
+                ("cat".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_sorted_at() {
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("generate")
+                .subcommand(Command::new("task-docs"))
+                .subcommand(Command::new("docs")),
+        );
+
+        let err = is_sorted_at(&cmd, &["generate"]).unwrap_err();
+        assert!(err.contains("in 'mycli generate'"), "{}", err);
+        assert!(is_sorted_at(&cmd, &["generate", "docs"]).is_ok());
+
+        let err = is_sorted_at(&cmd, &["missing"]).unwrap_err();
+        assert_eq!(err, "Subcommand 'missing' not found in 'mycli'");
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_assert_sorted_at_respects_config() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("generate")
+                .arg(Arg::new("style").short('s').long("style"))
+                .arg(Arg::new("output").short('o').long("output")),
+        );
+
+        assert_sorted_at(&cmd, &["generate"], &SortConfig::new().short_flags(false));
+    }
+
+    #[test]
+    fn test_full_listing_appends_expected_order() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli").subcommand(
+            Command::new("generate")
+                .arg(Arg::new("file"))
+                .arg(Arg::new("config").long("config"))
+                .arg(Arg::new("output").short('o').long("output"))
+                .subcommand(Command::new("task-docs"))
+                .subcommand(Command::new("completion")),
+        );
+
+        let config = SortConfig::new().full_listing(true);
+        let err = is_sorted_at_with_config(&cmd, &["generate"], &config).unwrap_err();
+        assert!(
+            err.ends_with(
+                "\n\nExpected order of 'mycli generate':\n  \
+                 Subcommands: [\"completion\", \"task-docs\"]\n  \
+                 Positionals: [\"file\"]\n  \
+                 Short flags: [\"-o\"]\n  \
+                 Long flags: [\"--config\"]"
+            ),
+            "{}",
+            err
+        );
+
+        let err = is_sorted_at_with_config(&cmd, &[], &SortConfig::default()).unwrap_err();
+        assert!(!err.contains("Expected order of"));
+    }
+
+    #[test]
+    fn test_depth_override_relaxes_nested_commands() {
+        let cmd = Command::new("mycli")
+            .subcommand(Command::new("add"))
+            .subcommand(
+                Command::new("plugin")
+                    .subcommand(Command::new("alpha"))
+                    .subcommand(Command::new("Beta"))
+                    .subcommand(Command::new("gamma")),
+            );
+
+        let strict = SortConfig::new();
+        let relaxed = SortConfig::new().at_depth(1, SortConfig::new().case_insensitive(true));
+
+        assert!(is_sorted_at_with_config(&cmd, &[], &relaxed).is_ok());
+        assert!(is_sorted_at_with_config(&cmd, &[], &strict).is_err());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "Subcommands in 'mycli' are not sorted")]
+    fn test_depth_override_keeps_root_strict() {
+        let cmd = Command::new("mycli")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("Zip"));
+
+        let config = SortConfig::new().at_depth(1, SortConfig::new().case_insensitive(true));
+        assert_sorted_at(&cmd, &[], &config);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_built_command_help_flags_ignored() {
+        use clap::Arg;
+
+        let mut cmd = Command::new("test")
+            .version("1.0")
+            .arg(Arg::new("output").short('o').long("output"))
+            .arg(Arg::new("config").long("config"));
+        cmd.build();
+
+        // -h and -V are appended after -o and --config by clap
+        assert!(cmd.get_arguments().any(|a| a.get_short() == Some('h')));
+        assert_sorted(&cmd);
+        assert_sorted_at(
+            &cmd,
+            &[],
+            &SortConfig::new().builtin_flags(BuiltinFlags::Last),
+        );
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "Help and version flags in 'test' must come last")]
+    fn test_builtin_flags_last_policy() {
+        use clap::{Arg, ArgAction};
+
+        let cmd = Command::new("test")
+            .disable_help_flag(true)
+            .arg(
+                Arg::new("help")
+                    .short('h')
+                    .long("help")
+                    .action(ArgAction::Help),
+            )
+            .arg(Arg::new("output").short('o').long("output"));
+
+        assert_sorted(&cmd);
+        assert_sorted_at(
+            &cmd,
+            &[],
+            &SortConfig::new().builtin_flags(BuiltinFlags::Last),
+        );
+    }
+
+    #[test]
+    fn test_builtin_shadowing() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .version("1.0")
+            .arg(Arg::new("host").short('h').long("host"))
+            .arg(Arg::new("verbose").short('V').long("verbose"));
+        let config = SortConfig::new().builtin_shadowing(true);
+
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+        let report = check_all(&cmd, &config);
+        let messages: Vec<_> = report.errors.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Argument 'host' in 'test' uses '-h', which clap also gives its help flag! Call `disable_help_flag(true)` if this is intended.",
+                "Argument 'verbose' in 'test' uses '-V', which clap also gives its version flag! Call `disable_version_flag(true)` if this is intended.",
+            ]
+        );
+
+        let cmd = cmd.disable_help_flag(true).disable_version_flag(true);
+        assert!(check_all(&cmd, &config).is_ok());
+
+        // Without a version, clap generates no version flag to shadow
+        let cmd = Command::new("test").arg(Arg::new("verbose").short('V'));
+        assert!(check_all(&cmd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_reserved_shorts() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("verbose").short('v'))
+            .subcommand(
+                Command::new("add")
+                    .arg(Arg::new("force").short('f'))
+                    .arg(Arg::new("version-spec").short('v')),
+            );
+
+        let config = SortConfig::new().reserve_short('v', "verbose");
+        let report = check_all(&cmd, &config);
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].path, ["test", "add"]);
+        assert_eq!(
+            report.errors[0].message,
+            "Argument 'version-spec' in 'test add' uses '-v', which is reserved for 'verbose'!"
+        );
+    }
+
+    #[test]
+    fn test_contiguous_headings() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("proxy").long("proxy").help_heading("Network"))
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("timeout").long("timeout").help_heading("Network"));
+
+        let config = SortConfig::new().contiguous_headings(true);
+        let report = check_all(&cmd, &config);
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(
+            report.errors[0].message,
+            "Arguments under 'Network' in 'test' are not declared together!\n\
+             Actual: [\"--proxy\", \"-d\", \"--timeout\"]\n\
+             Expected: [\"--proxy\", \"--timeout\", \"-d\"]"
+        );
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_sort_by_display_names() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("rm").display_name("delete"))
+            .subcommand(Command::new("ls").display_name("list"));
+
+        let config = SortConfig::new().display_names(true);
+        assert!(check_all(&cmd, &config).is_ok());
+
+        let report = check_all(&cmd, &SortConfig::default());
+        assert_eq!(report.errors.len(), 1);
+        assert!(
+            report.errors[0]
+                .message
+                .contains("Actual order: [\"add\", \"rm\", \"ls\"]")
+        );
+
+        let plan = fix_plan(&cmd.subcommand(Command::new("build")), &config);
+        let names: Vec<_> = plan.commands[0]
+            .subcommands
+            .iter()
+            .map(|m| (m.name.as_str(), m.to))
+            .collect();
+        assert_eq!(names, [("rm", 2), ("ls", 3), ("build", 1)]);
+    }
+
+    #[test]
+    fn test_explicit_order() {
+        let db = |names: &[&'static str]| {
+            names.iter().fold(Command::new("db"), |db, name| {
+                db.subcommand(Command::new(*name))
+            })
+        };
+        let config = SortConfig::new().expected_order(&["db"], &["create", "migrate", "drop"]);
+
+        let cmd = Command::new("test").subcommand(db(&["create", "migrate", "drop"]));
+        assert!(check_all(&cmd, &config).is_ok());
+        assert!(!check_all(&cmd, &SortConfig::default()).is_ok());
+
+        let cmd = Command::new("test").subcommand(db(&["create", "drop", "migrate"]));
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::ExplicitOrder);
+
+        let plan = fix_plan(&cmd, &config);
+        let moves: Vec<_> = plan.commands[0]
+            .subcommands
+            .iter()
+            .map(|m| (m.name.as_str(), m.to))
+            .collect();
+        assert_eq!(moves, [("drop", 2), ("migrate", 1)]);
+
+        // Missing and extra subcommands don't match either
+        let cmd = Command::new("test").subcommand(db(&["create", "migrate", "seed"]));
+        assert!(!check_all(&cmd, &config).is_ok());
+
+        // Skipped hidden subcommands stay where they are in the fix too
+        let cmd = Command::new("test").subcommand(
+            db(&["drop"])
+                .subcommand(Command::new("old").hide(true))
+                .subcommand(Command::new("migrate"))
+                .subcommand(Command::new("create")),
+        );
+        let config = config.skip_hidden(true);
+        assert_eq!(check_all(&cmd, &config).errors.len(), 1);
+        let plan = fix_plan(&cmd, &config);
+        let moves: Vec<_> = plan.commands[0]
+            .subcommands
+            .iter()
+            .map(|m| (m.name.as_str(), m.to))
+            .collect();
+        assert_eq!(moves, [("drop", 3), ("create", 0)]);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let unsorted = || {
+            Command::new("b")
+                .subcommand(Command::new("z"))
+                .subcommand(Command::new("y"))
+        };
+        let cmd = Command::new("test").subcommand(
+            Command::new("plugins")
+                .subcommand(Command::new("a").subcommand(unsorted()))
+                .subcommand(unsorted()),
+        );
+
+        let paths = |config: &SortConfig| -> Vec<String> {
+            check_all(&cmd, config)
+                .errors
+                .iter()
+                .map(|v| v.path.join(" "))
+                .collect()
+        };
+        assert_eq!(
+            paths(&SortConfig::default()),
+            ["test plugins a b", "test plugins b"]
+        );
+        assert_eq!(paths(&SortConfig::new().max_depth(2)), ["test plugins b"]);
+        assert!(paths(&SortConfig::new().max_depth(1)).is_empty());
+        assert!(fix_plan(&cmd, &SortConfig::new().max_depth(1)).is_empty());
+    }
+
+    #[test]
+    fn test_skip_hidden() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("zone").short('z').hide(true))
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("verbose").short('v'))
+            .subcommand(
+                Command::new("zap")
+                    .hide(true)
+                    .subcommand(Command::new("b"))
+                    .subcommand(Command::new("a")),
+            )
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        let config = SortConfig::new().skip_hidden(true);
+        assert!(check_all(&cmd, &config).is_ok());
+        assert!(fix_plan(&cmd, &config).is_empty());
+        assert_eq!(check_all(&cmd, &SortConfig::default()).errors.len(), 3);
+    }
+
+    #[test]
+    fn test_hidden_items_as_trailing_block() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("color").short('c').hide(true))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("zap").hide(true))
+            .subcommand(Command::new("old").hide(true));
+
+        let config = SortConfig::new().hidden_last(true);
+        let kinds: Vec<_> = check_all(&cmd, &config)
+            .errors
+            .iter()
+            .map(|v| v.kind)
+            .collect();
+        assert_eq!(kinds, [ViolationKind::UnsortedSubcommands]);
+        assert_eq!(check_all(&cmd, &SortConfig::default()).errors.len(), 2);
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("color").short('c').hide(true))
+            .arg(Arg::new("debug").short('d'))
+            .subcommand(Command::new("old").hide(true))
+            .subcommand(Command::new("add"));
+
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 2);
+        assert!(
+            report
+                .errors
+                .iter()
+                .all(|v| v.kind == ViolationKind::HiddenNotLast)
+        );
+        assert!(
+            report.errors[0]
+                .message
+                .starts_with("Hidden subcommands in 'test' must come after visible ones!")
+        );
+
+        let plan = fix_plan(&cmd, &config);
+        let moves = |moves: &[Move]| -> Vec<(String, usize, usize)> {
+            moves
+                .iter()
+                .map(|m| (m.name.clone(), m.from, m.to))
+                .collect()
+        };
+        assert_eq!(
+            moves(&plan.commands[0].subcommands),
+            [("old".to_string(), 0, 1), ("add".to_string(), 1, 0)]
+        );
+        assert_eq!(
+            moves(&plan.commands[0].args),
+            [("-c".to_string(), 0, 1), ("-d".to_string(), 1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_descending_direction() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("zone").long("zone"))
+            .arg(Arg::new("color").long("color"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        let config = SortConfig::new()
+            .direction(Direction::Descending)
+            .subcommand_direction(Direction::Ascending);
+        assert!(check_all(&cmd, &config).is_ok());
+
+        let kinds: Vec<_> = check_all(&cmd, &SortConfig::default())
+            .errors
+            .iter()
+            .map(|v| v.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ViolationKind::UnsortedShortFlags,
+                ViolationKind::UnsortedLongFlags
+            ]
+        );
+
+        let plan = fix_plan(&cmd, &SortConfig::new().direction(Direction::Descending));
+        assert_eq!(plan.commands[0].subcommands.len(), 2);
+        assert!(plan.commands[0].args.is_empty());
+    }
+
+    #[test]
+    fn test_positional_order() {
+        use clap::{Arg, ArgAction};
+
+        let cmd = Command::new("mycli")
+            .arg(Arg::new("files").action(ArgAction::Append).last(true))
+            .arg(Arg::new("output"))
+            .arg(Arg::new("input").required(true));
+
+        let config = SortConfig::new().positional_order(true);
+        let report = check_all(&cmd, &config);
+        let kinds: Vec<_> = report.errors.iter().map(|v| v.kind).collect();
+        assert_eq!(kinds, [ViolationKind::PositionalOrder]);
+        assert_eq!(report.errors[0].expected, ["input", "output", "files"]);
+
+        let cmd = Command::new("mycli")
+            .arg(Arg::new("input").required(true))
+            .arg(Arg::new("output"))
+            .arg(Arg::new("files").action(ArgAction::Append));
+        assert!(check_all(&cmd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_possible_values() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli")
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["yaml", "json", "toml"]),
+            )
+            .arg(Arg::new("level").value_parser(["debug", "info"]));
+
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+
+        let report = check_all(&cmd, &SortConfig::new().possible_values(true));
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::UnsortedPossibleValues);
+        assert!(
+            report.errors[0]
+                .message
+                .starts_with("Possible values of '--format' in 'mycli' are not sorted!"),
+            "{}",
+            report.errors[0]
+        );
+        assert_eq!(report.errors[0].expected, ["json", "toml", "yaml"]);
+    }
+
+    #[test]
+    fn test_alias_order() {
+        let cmd = Command::new("mycli")
+            .subcommand(Command::new("list").visible_alias("ls").alias("l"))
+            .subcommand(Command::new("remove").aliases(["del", "rm"]));
+        let config = SortConfig::new().alias_order(true);
+
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::UnsortedAliases);
+        assert!(
+            report.errors[0]
+                .message
+                .starts_with("Aliases of 'list' in 'mycli' are not sorted!"),
+            "{}",
+            report.errors[0]
+        );
+        assert_eq!(report.errors[0].expected, ["l", "ls"]);
+
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+
+        let cmd = Command::new("mycli").arg(
+            clap::Arg::new("color")
+                .long("color")
+                .visible_alias("colour")
+                .alias("clr"),
+        );
+
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(
+            report.errors[0]
+                .message
+                .starts_with("Aliases of '--color' in 'mycli' are not sorted!"),
+            "{}",
+            report.errors[0]
+        );
+        assert_eq!(report.errors[0].expected, ["--clr", "--colour"]);
+    }
+
+    #[test]
+    fn test_ignore_path() {
+        use clap::Arg;
+
+        let cmd = Command::new("mycli")
+            .subcommand(
+                Command::new("legacy")
+                    .arg(Arg::new("verbose").short('v'))
+                    .arg(Arg::new("debug").short('d'))
+                    .subcommand(
+                        Command::new("zap")
+                            .subcommand(Command::new("b"))
+                            .subcommand(Command::new("a")),
+                    )
+                    .subcommand(Command::new("add")),
+            )
+            .subcommand(
+                Command::new("modern")
+                    .subcommand(Command::new("b"))
+                    .subcommand(Command::new("a")),
+            );
+
+        let config = SortConfig::new().ignore_path(&["legacy"]);
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].path, ["mycli", "modern"]);
+
+        let plan = fix_plan(&cmd, &config);
+        assert_eq!(plan.commands.len(), 1);
+        assert_eq!(plan.commands[0].path, ["mycli", "modern"]);
+
+        assert_eq!(check_all(&cmd, &SortConfig::default()).errors.len(), 4);
+    }
+
+    #[test]
+    fn test_subcommand_comparator() {
+        // Shortest name first
+        let config = SortConfig::new()
+            .subcommand_comparator(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        let cmd = Command::new("test")
+            .subcommand(Command::new("ls"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("remove"));
+        assert!(is_sorted_with_config(&cmd, &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("ls"))
+            .subcommand(
+                Command::new("nested")
+                    .subcommand(Command::new("zz"))
+                    .subcommand(Command::new("a")),
+            );
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 2);
+        assert!(
+            report.errors[0]
+                .message
+                .starts_with("Subcommands in 'test' are not sorted!"),
+            "{}",
+            report.errors[0]
+        );
+        assert_eq!(report.errors[1].path, ["test", "nested"]);
+    }
+
+    #[test]
+    fn test_long_flag_comparator() {
+        // Shortest name first
+        let config = SortConfig::new()
+            .alias_order(true)
+            .long_flag_comparator(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        let cmd = Command::new("test")
+            .arg(
+                clap::Arg::new("zip")
+                    .long("zip")
+                    .visible_aliases(["gz", "bzip"]),
+            )
+            .arg(clap::Arg::new("archive").long("archive"));
+        assert!(is_sorted_with_config(&cmd, &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+
+        let cmd = Command::new("test")
+            .arg(clap::Arg::new("archive").long("archive"))
+            .arg(clap::Arg::new("zip").long("zip"));
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].expected, ["--zip", "--archive"]);
+        assert_eq!(fix_plan(&cmd, &config).commands.len(), 1);
+    }
+
+    #[test]
+    fn test_violations_carry_orders() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("pattern").long("pattern").value_name("pat"))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"));
+
+        let config = SortConfig::new().value_name_style(ValueNameStyle::ScreamingSnakeCase);
+        let report = check_all(&cmd, &config);
+        let orders: Vec<_> = report
+            .errors
+            .iter()
+            .map(|v| match v.kind {
+                ViolationKind::UnsortedSubcommands | ViolationKind::UnsortedShortFlags => {
+                    (v.actual.clone(), v.expected.clone())
+                }
+                _ => {
+                    assert!(v.actual.is_empty() && v.expected.is_empty());
+                    (vec![], vec![])
+                }
+            })
+            .collect();
+        assert_eq!(
+            orders,
+            [
+                (
+                    vec!["list".to_string(), "add".to_string()],
+                    vec!["add".to_string(), "list".to_string()]
+                ),
+                (
+                    vec!["-v".to_string(), "-d".to_string()],
+                    vec!["-d".to_string(), "-v".to_string()]
+                ),
+                (vec![], vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_order() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("color").long("color"))
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("file"));
+
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+
+        let config = SortConfig::new().group_order(true);
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::WrongGroupOrder);
+
+        let plan = fix_plan(&cmd, &config);
+        let moves: Vec<(&str, usize, usize)> = plan.commands[0]
+            .args
+            .iter()
+            .map(|m| (m.name.as_str(), m.from, m.to))
+            .collect();
+        assert_eq!(moves, [("--color", 0, 2), ("file", 2, 0)]);
+    }
+
+    #[test]
+    fn test_short_key() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("debug").short('d'))
+            .arg(Arg::new("verbose").short('v'))
+            .arg(Arg::new("extract").short('x').short_alias('a'));
+
+        assert!(check_all(&cmd, &SortConfig::default()).is_ok());
+
+        let config = SortConfig::new().short_key(ShortKey::First);
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert!(
+            report.errors[0]
+                .message
+                .contains("\"-x (aliases: -a)\", \"-d\""),
+            "{}",
+            report.errors[0]
+        );
+
+        let plan = fix_plan(&cmd, &config);
+        let extract = plan.commands[0].args.iter().find(|m| m.name == "-x");
+        assert_eq!(extract.map(|m| (m.from, m.to)), Some((2, 0)));
+    }
+
+    #[test]
+    fn test_short_key_long() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("all").short('z').long("all"))
+            .arg(Arg::new("output").short('f').long("output"))
+            .arg(Arg::new("quiet").short('q'))
+            .arg(Arg::new("config").long("config"));
+
+        let config = SortConfig::new().short_key(ShortKey::Long);
+        assert!(check_all(&cmd, &config).is_ok());
+
+        let report = check_all(&cmd, &SortConfig::default());
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].expected, ["-f", "-q", "-z"]);
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("output").short('f').long("output"))
+            .arg(Arg::new("all").short('a').long("all"));
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors[0].expected, ["-a (--all)", "-f (--output)"]);
+    }
+
+    #[test]
+    fn test_pinned_subcommands_exempt_from_alphabetical_order() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("init"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("version"))
+            .subcommand(Command::new("completions"));
+
+        let config =
+            SortConfig::new()
+                .pin_first(&["init"])
+                .pin_last(&["version", "completions", "help"]);
+
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+    }
+
+    #[test]
+    fn test_pinned_subcommands_out_of_place() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("help"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        let config = SortConfig::new().pin_last(&["help"]);
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+
+        assert!(
+            err.contains("Pinned subcommands in 'test' are out of place"),
+            "{}",
+            err
+        );
+        assert!(err.contains(r#"["add", "list", "help"]"#), "{}", err);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "are not sorted alphabetically")]
+    fn test_pinned_subcommands_still_check_the_rest() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("help"));
+
+        assert_sorted_at(&cmd, &[], &SortConfig::new().pin_last(&["help"]));
+    }
+
+    #[test]
+    fn test_prefix_grouped_subcommands() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("cache:clear"))
+            .subcommand(Command::new("cache:prune"))
+            .subcommand(Command::new("cache2"))
+            .subcommand(Command::new("config:get"))
+            .subcommand(Command::new("config:set"));
+
+        let config = SortConfig::new().group_by(GroupBy::Prefix(':'));
+
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+    }
+
+    #[test]
+    fn test_categorized_subcommands() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("remove"))
+            .subcommand(Command::new("backup"))
+            .subcommand(Command::new("restore"))
+            .subcommand(Command::new("misc"));
+
+        let config = SortConfig::new()
+            .category("core", &["add", "remove"])
+            .category("admin", &["backup", "restore"]);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+
+        let reversed = SortConfig::new()
+            .category("admin", &["backup", "restore"])
+            .category("core", &["add", "remove"]);
+        let err = is_sorted_at_with_config(&cmd, &[], &reversed).unwrap_err();
+        assert!(
+            err.contains(r#"["backup", "restore", "add", "remove", "misc"]"#),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_digit_and_symbol_shorts_in_char_order_by_default() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("one").short('1').long("one"))
+            .arg(Arg::new("help-me").short('?').long("help-me"))
+            .arg(Arg::new("all").short('a').long("all"))
+            .arg(Arg::new("tilde").short('~').long("tilde"));
+
+        assert!(is_sorted(&cmd).is_ok());
+    }
+
+    #[test]
+    fn test_digit_and_symbol_shorts_before_letters() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("hash").short('#').long("hash"))
+            .arg(Arg::new("one").short('1').long("one"))
+            .arg(Arg::new("nine").short('9').long("nine"))
+            .arg(Arg::new("all").short('a').long("all"))
+            .arg(Arg::new("tilde").short('~').long("tilde"));
+
+        let config = SortConfig::new().non_alpha_shorts(NonAlphaShorts::BeforeLetters);
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+        assert!(
+            err.contains(r#"Expected: ["-#", "-~", "-1", "-9", "-a"]"#),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_digit_and_symbol_shorts_after_letters() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("all").short('a').long("all"))
+            .arg(Arg::new("Zone").short('Z').long("zone"))
+            .arg(Arg::new("one").short('1').long("one"))
+            .arg(Arg::new("nine").short('9').long("nine"))
+            .arg(Arg::new("hash").short('#').long("hash"));
+
+        let config = SortConfig::new().non_alpha_shorts(NonAlphaShorts::AfterLetters);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+    }
+
+    #[test]
+    fn test_long_flags_ignore_separators() {
+        use clap::{Arg, ArgAction};
+
+        let cmd = Command::new("test")
+            .arg(
+                Arg::new("nocolor")
+                    .long("nocolor")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("no-verify")
+                    .long("no-verify")
+                    .action(ArgAction::SetTrue),
+            );
+
+        let config = SortConfig::new().ignore_separators(true);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+    }
+
+    #[test]
+    fn test_ignored_subcommands_and_args() {
+        use clap::{Arg, ArgAction};
+
+        let cmd = Command::new("test")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count),
+            )
+            .arg(
+                Arg::new("debug")
+                    .short('d')
+                    .long("debug")
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(Command::new("zap"))
+            .subcommand(Command::new("add"));
+
+        let config = SortConfig::new()
+            .ignore_args(&["verbose"])
+            .ignore_subcommands(&["zap"]);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+        assert!(is_sorted(&cmd).is_err());
+    }
+
+    #[test]
+    fn test_with_conventions_accepts_built_command() {
+        use clap::{Arg, ArgAction};
+
+        let mut cmd = Command::new("test")
+            .version("1.0")
+            .arg(
+                Arg::new("debug")
+                    .short('d')
+                    .long("debug")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count),
+            )
+            .arg(Arg::new("color").long("color"))
+            .arg(Arg::new("all").long("all").action(ArgAction::SetTrue))
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+        cmd.build();
+
+        // clap appends a `help` subcommand, and --color precedes --all
+        assert!(cmd.find_subcommand("help").is_some());
+        assert!(is_sorted(&cmd).is_err());
+        assert!(is_sorted_at_with_config(&cmd, &[], &SortConfig::with_conventions()).is_ok());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_external_subcommands_ignored_for_ordering() {
+        let cmd = Command::new("test")
+            .allow_external_subcommands(true)
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"));
+
+        assert_sorted(&cmd);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(
+        expected = "Command 'test' allows external subcommands but declares optional or variadic positional arguments [\"file\"]"
+    )]
+    fn test_external_subcommands_with_positionals() {
+        let cmd = Command::new("test")
+            .allow_external_subcommands(true)
+            .arg(clap::Arg::new("file"))
+            .subcommand(Command::new("add"));
+
+        assert!(is_sorted(&cmd).is_ok());
+        assert_sorted_at(&cmd, &[], &SortConfig::new().external_subcommands(true));
+    }
+
+    #[test]
+    fn test_external_subcommands_with_required_positional() {
+        use clap::Arg;
+
+        let config = SortConfig::new().external_subcommands(true);
+        let cmd = Command::new("test")
+            .allow_external_subcommands(true)
+            .arg(Arg::new("target").required(true));
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+
+        let cmd = Command::new("test")
+            .allow_external_subcommands(true)
+            .arg(Arg::new("targets").required(true).num_args(1..));
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_err());
+    }
+
+    #[test]
+    fn test_multicall_applets_validated_as_roots() {
+        use clap::Arg;
+
+        let cmd = Command::new("busybox").multicall(true).subcommand(
+            Command::new("ls")
+                .arg(Arg::new("long").short('l').long("long"))
+                .arg(Arg::new("all").short('a').long("all")),
+        );
+
+        let err = is_sorted(&cmd).unwrap_err();
+        assert!(
+            err.starts_with("Flags with short options in 'ls' are not sorted"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_ignore_path_in_multicall_applet() {
+        use clap::Arg;
+
+        let unsorted = |name: &'static str| {
+            Command::new(name)
+                .arg(Arg::new("long").short('l').long("long"))
+                .arg(Arg::new("all").short('a').long("all"))
+        };
+        let cmd = Command::new("busybox")
+            .multicall(true)
+            .subcommand(unsorted("cp"))
+            .subcommand(unsorted("ls").subcommand(unsorted("tree")));
+
+        let config = SortConfig::new().ignore_path(&["ls"]);
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].path, ["cp"]);
+        let plan = fix_plan(&cmd, &config);
+        assert_eq!(plan.commands.len(), 1);
+        assert_eq!(plan.commands[0].path, ["cp"]);
+
+        let config = SortConfig::new().ignore_path(&["ls", "tree"]);
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors.iter().all(|v| v.path != ["ls", "tree"]));
+
+        // Commands compared with each other are reported and ignored the same way
+        let cmd = Command::new("busybox")
+            .multicall(true)
+            .subcommand(Command::new("cp").arg(Arg::new("all").long("all")))
+            .subcommand(
+                Command::new("ls")
+                    .next_display_order(None)
+                    .arg(Arg::new("all").long("all"))
+                    .arg(Arg::new("long").long("long")),
+            );
+        let config = SortConfig::new().display_order_consistency(true);
+        assert_eq!(check_all(&cmd, &config).errors.len(), 1);
+        let config = config.ignore_path(&["ls"]);
+        assert!(check_all(&cmd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_multicall_applet_order_policy() {
+        let cmd = Command::new("busybox")
+            .multicall(true)
+            .subcommand(Command::new("true"))
+            .subcommand(Command::new("false"));
+
+        assert!(is_sorted(&cmd).is_err());
+        let config = SortConfig::new().multicall_applets(false);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_rendered_help_option() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add").display_order(2))
+            .subcommand(Command::new("list").display_order(1));
+
+        assert_sorted(&cmd);
+        let config = SortConfig::new().rendered_help(true);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_err());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_display_order_consistency_option() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("list"))
+            .subcommand(
+                Command::new("sub")
+                    .next_display_order(None)
+                    .arg(Arg::new("debug").short('d').long("debug"))
+                    .arg(Arg::new("force").short('f').long("force")),
+            );
+
+        assert_sorted(&cmd);
+        let config = SortConfig::new().display_order_consistency(true);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_err());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "alias 'rm' in 'test' is used by both 'delete' and 'remove'")]
+    fn test_alias_collision() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("delete").visible_alias("rm"))
+            .subcommand(Command::new("remove").alias("rm"));
+
+        assert_sorted_at(&cmd, &[], &SortConfig::new().alias_collisions(true));
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "alias 'list' in 'test' is used by both 'list' and 'ls'")]
+    fn test_alias_collides_with_name() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("ls").alias("list"));
+
+        assert_sorted_at(&cmd, &[], &SortConfig::new().alias_collisions(true));
+    }
+
+    #[test]
+    #[cfg(feature = "env")]
+    fn test_env_pattern() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("dry-run").long("dry-run").env("MYCLI_DRY_RUN"))
+            .arg(Arg::new("output").long("output").env("MYCLI_OUT"));
+
+        let config = SortConfig::new().env_pattern("MYCLI_{ID}");
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+        assert!(
+            err.contains(r#"for 'output' in 'test' is "MYCLI_OUT", expected "MYCLI_OUTPUT""#),
+            "{}",
+            err
+        );
+        assert!(is_sorted(&cmd).is_ok());
+    }
+
+    #[test]
+    fn test_value_name_style() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("input").value_name("INPUT_FILE"))
+            .arg(Arg::new("config").long("config"))
+            .arg(Arg::new("output").long("output").value_name("out-file"));
+
+        let config = SortConfig::new().value_name_style(ValueNameStyle::ScreamingSnakeCase);
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+        assert_eq!(
+            err,
+            "Value name 'out-file' of 'output' in 'test' is not ScreamingSnakeCase!"
+        );
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "Positional argument 'files' in 'test' takes multiple values")]
+    fn test_variadic_positional_not_last() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("files").num_args(1..))
+            .arg(Arg::new("dest"));
+
+        assert_sorted(&cmd);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_variadic_positional_before_last() {
+        use clap::Arg;
+
+        let mut cmd = Command::new("test")
+            .arg(Arg::new("files").num_args(1..))
+            .arg(Arg::new("rest").num_args(1..).last(true));
+
+        assert_sorted(&cmd);
+        let matches = cmd
+            .try_get_matches_from_mut(["test", "a", "b", "--", "c"])
+            .unwrap();
+        assert_eq!(matches.get_many::<String>("files").unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_variadic_positional_before_required() {
+        use clap::Arg;
+
+        // cp SRC... DEST
+        let cmd = Command::new("test")
+            .arg(Arg::new("src").num_args(1..).required(true))
+            .arg(Arg::new("dest").required(true));
+
+        assert_sorted(&cmd);
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_variadic_positional_with_terminator() {
+        use clap::Arg;
+
+        let cmd = Command::new("test")
+            .arg(Arg::new("files").num_args(1..).value_terminator(";"))
+            .arg(Arg::new("dest"))
+            .arg(Arg::new("rest").num_args(0..));
+
+        assert_sorted(&cmd);
+    }
+
+    #[test]
+    fn test_kebab_case_subcommands() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add-item"))
+            .subcommand(Command::new("legacy").subcommand(Command::new("Import_CSV")));
+
+        let config = SortConfig::new().kebab_case_subcommands(true);
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+        assert_eq!(
+            err,
+            "Subcommand 'Import_CSV' in 'test legacy' is not lowercase kebab-case!"
+        );
+
+        let config = config.allow_subcommand_name(&["legacy", "Import_CSV"]);
+        assert!(is_sorted_at_with_config(&cmd, &[], &config).is_ok());
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    #[should_panic(expected = "Subcommand 'synchronize' in 'test' is longer than 8 characters")]
+    fn test_max_subcommand_len() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(Command::new("synchronize"));
+
+        assert_sorted_at(&cmd, &[], &SortConfig::new().max_subcommand_len(8));
+    }
+
+    #[test]
+    fn test_allowed_verbs() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("add"))
+            .subcommand(
+                Command::new("config")
+                    .subcommand(Command::new("list-keys"))
+                    .subcommand(Command::new("wipe")),
+            );
+
+        let config = SortConfig::new().allowed_verbs(&["add", "list", "remove"]);
+        let err = is_sorted_at_with_config(&cmd, &[], &config).unwrap_err();
+        assert!(
+            err.starts_with(
+                "Subcommand 'wipe' in 'test config' does not start with an allowed verb"
+            ),
+            "{}",
+            err
+        );
+    }
+
+    #[cfg(feature = "assert")]
+    #[test]
+    fn test_panic_names_caller() {
+        let cmd = Command::new("test")
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"));
+
+        let check = || assert_sorted(&cmd);
+        let line = line!() - 1;
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(check)).unwrap_err();
+        let msg = payload.downcast_ref::<String>().unwrap();
+
+        assert!(msg.contains("Subcommands in 'test'"), "{}", msg);
+        assert!(
+            msg.contains(&format!("Checked at: {}:{}:", file!(), line)),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn test_check_all_collects_every_violation() {
+        let cmd = Command::new("test")
+            .arg(clap::Arg::new("verbose").short('v').long("verbose"))
+            .arg(clap::Arg::new("debug").short('d').long("debug"))
+            .subcommand(Command::new("list"))
+            .subcommand(
+                Command::new("add")
+                    .subcommand(Command::new("zebra"))
+                    .subcommand(Command::new("apple")),
+            );
+
+        let report = check_all(&cmd, &SortConfig::default());
+
+        let kinds: Vec<_> = report.errors.iter().map(|v| v.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ViolationKind::UnsortedSubcommands,
+                ViolationKind::UnsortedShortFlags,
+                ViolationKind::UnsortedSubcommands,
+            ]
+        );
+        assert_eq!(report.errors[2].path, ["test", "add"]);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_warnings_do_not_fail_validation() {
+        let cmd = Command::new("test")
+            .arg(clap::Arg::new("verbose").short('v').long("verbose"))
+            .arg(clap::Arg::new("debug").short('d').long("debug"))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"));
+
+        let config =
+            SortConfig::new().severity(ViolationKind::UnsortedSubcommands, Severity::Warning);
+        let err = is_sorted_with_config(&cmd, &config).unwrap_err();
+        assert!(
+            err.starts_with("Flags with short options in 'test'"),
+            "{}",
+            err
+        );
+
+        let report = check_all(&cmd, &config);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].severity, Severity::Warning);
+
+        let config = config.severity(ViolationKind::UnsortedShortFlags, Severity::Warning);
+        assert!(is_sorted_with_config(&cmd, &config).is_ok());
+        assert!(check_all(&cmd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_violations_are_ordered_by_path_then_kind() {
+        let cmd = Command::new("test")
+            .arg(clap::Arg::new("verbose").short('v'))
+            .arg(clap::Arg::new("debug").short('d'))
+            .subcommand(
+                Command::new("zebra")
+                    .next_display_order(None)
+                    .subcommand(Command::new("b"))
+                    .subcommand(Command::new("a")),
+            )
+            .subcommand(
+                Command::new("apple")
+                    .subcommand(Command::new("y"))
+                    .subcommand(Command::new("x")),
+            );
+
+        let config = SortConfig::new().display_order_consistency(true);
+        let report = check_all(&cmd, &config);
+
+        let found: Vec<_> = report
+            .errors
+            .iter()
+            .map(|v| (v.path.join(" "), v.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("test".to_string(), ViolationKind::UnsortedSubcommands),
+                ("test".to_string(), ViolationKind::UnsortedShortFlags),
+                ("test zebra".to_string(), ViolationKind::UnsortedSubcommands),
+                (
+                    "test zebra".to_string(),
+                    ViolationKind::InconsistentDisplayOrder
+                ),
+                ("test apple".to_string(), ViolationKind::UnsortedSubcommands),
+            ]
+        );
+        assert_eq!(check_all(&cmd, &config), report);
+    }
+
+    #[test]
+    fn test_max_violations_caps_report() {
+        let cmd = Command::new("test")
+            .subcommand(
+                Command::new("b")
+                    .subcommand(Command::new("z"))
+                    .subcommand(Command::new("y")),
+            )
+            .subcommand(
+                Command::new("a")
+                    .subcommand(Command::new("z"))
+                    .subcommand(Command::new("y")),
+            );
+
+        let report = check_all(&cmd, &SortConfig::new().max_violations(2));
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[1].path, ["test", "b"]);
+        assert_eq!(report.omitted, 1);
+        assert!(!report.is_ok());
+
+        let report = check_all(&cmd, &SortConfig::new().max_violations(3));
+        assert_eq!(report.errors.len(), 3);
+        assert_eq!(report.omitted, 0);
+
+        let config = SortConfig::new().max_violations(2);
+        let mut cache = CheckCache::new();
+        for _ in 0..2 {
+            let cached = check_all_cached(&cmd, &config, &mut cache);
+            assert_eq!(cached, check_all(&cmd, &config));
+        }
+    }
+
+    #[test]
+    fn test_disabled_rule_is_not_reported() {
+        let cmd = Command::new("test")
+            .arg(clap::Arg::new("verbose").short('v'))
+            .arg(clap::Arg::new("debug").short('d'))
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("add"));
+
+        let config = SortConfig::new().disable(ViolationKind::UnsortedSubcommands);
+        let report = check_all(&cmd, &config);
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, ViolationKind::UnsortedShortFlags);
+    }
+
+    #[test]
+    fn test_check_commands_keys_by_name() {
+        let unsorted = || {
+            Command::new("tool")
+                .subcommand(Command::new("b"))
+                .subcommand(Command::new("a"))
+        };
+        let sorted = Command::new("app").subcommand(Command::new("a"));
+
+        let reports = check_commands([&unsorted(), &sorted, &unsorted()], &SortConfig::default());
+
+        assert_eq!(reports.keys().collect::<Vec<_>>(), ["app", "tool"]);
+        assert!(reports["app"].is_empty());
+        assert_eq!(reports["tool"].errors.len(), 2);
+    }
+
+    #[cfg(feature = "inventory")]
+    mod registered {
+        use super::*;
+
+        #[derive(Parser)]
+        #[command(name = "registered-sorted")]
+        struct Sorted {
+            #[command(subcommand)]
+            command: SortedCommands,
+        }
+
+        #[derive(Subcommand)]
+        enum SortedCommands {
+            Add,
+            List,
+        }
+
+        #[derive(Parser)]
+        #[command(name = "registered-unsorted")]
+        struct Unsorted {
+            #[command(subcommand)]
+            command: UnsortedCommands,
+        }
+
+        #[derive(Subcommand)]
+        enum UnsortedCommands {
+            List,
+            Add,
+        }
+
+        crate::register!(Sorted);
+        crate::register!(Unsorted);
+
+        #[test]
+        fn test_validate_registered() {
+            let reports = validate_registered(&SortConfig::default());
+
+            assert!(reports["registered-sorted"].is_ok());
+            assert_eq!(reports["registered-unsorted"].errors.len(), 1);
+        }
+    }
+
+    #[cfg(feature = "log")]
+    mod logged {
+        use super::*;
+        use std::sync::Mutex;
+
+        struct Capture(Mutex<Vec<(log::Level, String)>>);
+
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                if record.target() == "clap_sort" {
+                    let message = record.args().to_string();
+                    self.0.lock().unwrap().push((record.level(), message));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+        #[test]
+        fn test_check_and_log() {
+            log::set_logger(&CAPTURE).unwrap();
+            log::set_max_level(log::LevelFilter::Warn);
+
+            let cmd = Command::new("logged")
+                .subcommand(
+                    Command::new("list")
+                        .subcommand(Command::new("b"))
+                        .subcommand(Command::new("a")),
+                )
+                .subcommand(Command::new("add"));
+            let config = SortConfig::new().max_violations(1);
+            let report = check_and_log(&cmd, &config);
+
+            let records = CAPTURE.0.lock().unwrap();
+            let expected = [
+                report.errors[0].to_string(),
+                "1 more violation(s) in 'logged' not shown".to_string(),
+            ];
+            assert_eq!(records.len(), 2);
+            for ((level, message), expected) in records.iter().zip(expected) {
+                assert_eq!(*level, log::Level::Warn);
+                assert_eq!(*message, expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    mod traced {
+        use super::*;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Records each span as its name followed by its fields.
+        #[derive(Clone, Default)]
+        struct Spans(Arc<Mutex<Vec<String>>>);
+
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push_str(&format!(" {}={:?}", field.name(), value));
+            }
+        }
+
+        impl Subscriber for Spans {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = Fields(span.metadata().name().to_string());
+                span.record(&mut fields);
+                let mut spans = self.0.lock().unwrap();
+                spans.push(fields.0);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        #[test]
+        fn test_spans_per_command_and_rule() {
+            let spans = Spans::default();
+            let cmd = Command::new("mycli").subcommand(Command::new("add"));
+            let config = SortConfig::new().variadic_positionals(false);
+            tracing::subscriber::with_default(spans.clone(), || check_all(&cmd, &config));
+
+            let spans = spans.0.lock().unwrap();
+            assert_eq!(
+                *spans,
+                [
+                    "check root=\"mycli\"",
+                    "command path=mycli",
+                    "rule rule=\"subcommand-order\"",
+                    "rule rule=\"argument-order\"",
+                    "command path=mycli add",
+                    "rule rule=\"subcommand-order\"",
+                    "rule rule=\"argument-order\"",
+                ]
+            );
+        }
+    }
 }
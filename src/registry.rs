@@ -0,0 +1,63 @@
+//! Link-time registration of commands to validate.
+//!
+//! Each binary or plugin crate calls [`register!`](crate::register) once for
+//! its CLI type, and a single test calls
+//! [`validate_registered`](crate::validate_registered) to check everything
+//! linked into the test binary, without a hand-maintained list.
+
+/// A command type submitted with [`register!`](crate::register).
+#[doc(hidden)]
+pub struct Registration {
+    command: fn() -> clap::Command,
+}
+
+impl Registration {
+    #[doc(hidden)]
+    pub const fn new(command: fn() -> clap::Command) -> Self {
+        Self { command }
+    }
+}
+
+inventory::collect!(Registration);
+
+/// Builds the command of every registered type.
+pub(crate) fn registered_commands() -> Vec<clap::Command> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .map(|registration| (registration.command)())
+        .collect()
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use clap::CommandFactory;
+    pub use inventory;
+}
+
+/// Registers a `CommandFactory` type, such as a `#[derive(Parser)]` struct,
+/// for [`validate_registered`](crate::validate_registered).
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+///
+/// #[derive(Parser)]
+/// #[command(name = "mycli")]
+/// struct Cli {
+///     #[arg(short, long)]
+///     verbose: bool,
+/// }
+///
+/// clap_sort::register!(Cli);
+/// ```
+#[macro_export]
+macro_rules! register {
+    ($ty:ty) => {
+        $crate::__private::inventory::submit! {
+            $crate::Registration::new(
+                <$ty as $crate::__private::CommandFactory>::command
+            )
+        }
+    };
+}
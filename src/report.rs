@@ -0,0 +1,234 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::{Severity, SortConfig, Violation};
+
+/// Every violation found in a command tree, split by severity.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Command;
+/// use clap_sort::{Severity, SortConfig, ViolationKind};
+///
+/// let cmd = Command::new("mycli")
+///     .subcommand(Command::new("list"))
+///     .subcommand(Command::new("add"));
+///
+/// let config = SortConfig::new().severity(ViolationKind::UnsortedSubcommands, Severity::Warning);
+/// let report = clap_sort::check_all(&cmd, &config);
+///
+/// assert!(report.is_ok());
+/// assert_eq!(report.warnings.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Violations that fail validation.
+    pub errors: Vec<Violation>,
+    /// Violations that are only reported.
+    pub warnings: Vec<Violation>,
+    /// Violations left out because of
+    /// [`SortConfig::max_violations`](crate::SortConfig::max_violations).
+    pub omitted: usize,
+    /// The config files that governed the checks, nearest first, from
+    /// [`SortConfig::config_files`](crate::SortConfig::config_files).
+    pub config_files: Vec<PathBuf>,
+}
+
+/// Violations collected while checking, up to an optional cap.
+///
+/// Past the cap, violations are only counted, so checking a huge tree with
+/// [`SortConfig::max_violations`] keeps at most that many in memory.
+#[derive(Debug, Default)]
+pub(crate) struct Collector {
+    pub(crate) violations: Vec<Violation>,
+    pub(crate) omitted: usize,
+    max: Option<usize>,
+}
+
+impl Collector {
+    pub(crate) fn new(max: Option<usize>) -> Self {
+        Self {
+            max,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn is_capped(&self) -> bool {
+        self.max.is_some()
+    }
+
+    pub(crate) fn extend(&mut self, found: impl IntoIterator<Item = Violation>) {
+        for violation in found {
+            if self.max.is_some_and(|max| self.violations.len() >= max) {
+                self.omitted += 1;
+            } else {
+                self.violations.push(violation);
+            }
+        }
+    }
+}
+
+impl Report {
+    pub(crate) fn from_collector(collected: Collector, config: &SortConfig) -> Self {
+        let (errors, warnings) = collected
+            .violations
+            .into_iter()
+            .partition(|v| v.severity == Severity::Error);
+        Self {
+            errors,
+            warnings,
+            omitted: collected.omitted,
+            config_files: config.config_files.clone(),
+        }
+    }
+
+    /// Returns true if there are no errors. Warnings are allowed.
+    ///
+    /// A capped report with omitted violations is never ok, since the
+    /// omitted ones may be errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty() && self.omitted == 0
+    }
+
+    /// Returns true if there are neither errors nor warnings.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty() && self.omitted == 0
+    }
+
+    /// Groups the violations by command path, in the order the commands
+    /// were checked. Within a command, errors come before warnings.
+    pub fn by_command(&self) -> Vec<(&[String], Vec<&Violation>)> {
+        let mut groups: Vec<(&[String], Vec<&Violation>)> = Vec::new();
+        for violation in self.errors.iter().chain(&self.warnings) {
+            match groups.iter_mut().find(|(path, _)| *path == violation.path) {
+                Some((_, violations)) => violations.push(violation),
+                None => groups.push((&violation.path, vec![violation])),
+            }
+        }
+        groups
+    }
+}
+
+/// Lists the violations under their command.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use clap_sort::SortConfig;
+///
+/// let cmd = Command::new("mycli").subcommand(
+///     Command::new("generate")
+///         .arg(Arg::new("verbose").short('v'))
+///         .arg(Arg::new("debug").short('d')),
+/// );
+///
+/// let report = clap_sort::check_all(&cmd, &SortConfig::default());
+/// assert_eq!(
+///     report.to_string(),
+///     r#"mycli generate:
+///   error: Flags with short options in 'mycli generate' are not sorted!
+///     Actual: ["-v", "-d"]
+///     Expected: ["-d", "-v"]
+/// "#
+/// );
+/// ```
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, violations) in self.by_command() {
+            writeln!(f, "{}:", path.join(" "))?;
+            for violation in violations {
+                let label = match violation.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                let mut lines = violation.message.lines();
+                writeln!(f, "  {}: {}", label, lines.next().unwrap_or_default())?;
+                for line in lines {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        }
+        if self.omitted > 0 {
+            writeln!(f, "{} more violation(s) not shown", self.omitted)?;
+        }
+        if !self.is_empty() && !self.config_files.is_empty() {
+            let files: Vec<String> = self
+                .config_files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            writeln!(f, "configured by {}", files.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Arg, Command};
+
+    use super::Collector;
+    use crate::{Severity, SortConfig, Violation, ViolationKind};
+
+    #[test]
+    fn test_collector_counts_past_cap() {
+        let violation = Violation::new(ViolationKind::UnsortedSubcommands, &["mycli"], "x".into());
+        let mut collected = Collector::new(Some(2));
+        collected.extend(vec![violation; 5]);
+
+        assert_eq!(collected.violations.len(), 2);
+        assert_eq!(collected.omitted, 3);
+    }
+
+    #[test]
+    fn test_display_groups_by_command() {
+        let cmd = Command::new("mycli")
+            .subcommand(Command::new("list"))
+            .subcommand(
+                Command::new("add")
+                    .arg(Arg::new("verbose").short('v'))
+                    .arg(Arg::new("debug").short('d'))
+                    .arg(Arg::new("zone").long("zone"))
+                    .arg(Arg::new("color").long("color")),
+            );
+        let config =
+            SortConfig::new().severity(ViolationKind::UnsortedLongFlags, Severity::Warning);
+        let report = crate::check_all(&cmd, &config);
+
+        let groups: Vec<(String, usize)> = report
+            .by_command()
+            .iter()
+            .map(|(path, violations)| (path.join(" "), violations.len()))
+            .collect();
+        assert_eq!(
+            groups,
+            [("mycli".to_string(), 1), ("mycli add".to_string(), 2)]
+        );
+
+        let text = report.to_string();
+        assert!(
+            text.starts_with("mycli:\n  error: Subcommands in 'mycli' are not sorted"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("mycli add:\n  error: Flags with short options"),
+            "{}",
+            text
+        );
+        assert!(text.contains("\n  warning: Long-only flags"), "{}", text);
+        assert!(text.contains("\n    Actual: "), "{}", text);
+        assert!(!text.contains("configured by"), "{}", text);
+
+        let mut config = config;
+        config.config_files = vec!["clap-sort.toml".into()];
+        let text = crate::check_all(&cmd, &config).to_string();
+        assert!(
+            text.ends_with("\nconfigured by clap-sort.toml\n"),
+            "{}",
+            text
+        );
+    }
+}
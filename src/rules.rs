@@ -0,0 +1,1047 @@
+//! The individual checks run against each command.
+//!
+//! Every check appends what it finds to `out` rather than stopping at the
+//! first problem, so one pass over a tree can report everything at once.
+
+use crate::diff::order_details;
+use crate::{
+    BuiltinFlags, Direction, ShortKey, SortConfig, ValueNameStyle, Violation, ViolationKind,
+};
+
+/// Runs every enabled per-command check on `cmd`.
+pub(crate) fn check_command(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    // External subcommands have no entry in `get_subcommands()`, so they
+    // never take part in ordering; only check that positionals can't shadow them
+    if config.external_subcommands && cmd.is_allow_external_subcommands_set() {
+        run_rule("external-subcommands", out, |out| {
+            check_external_subcommands(cmd, path, out)
+        });
+    }
+
+    // Check subcommands (the applets, for a multicall binary)
+    let check_subcommands = if cmd.is_multicall_set() {
+        config.multicall_applets
+    } else {
+        config.subcommands
+    };
+    if check_subcommands {
+        run_rule("subcommand-order", out, |out| {
+            // An explicit order covers every subcommand, hidden or not
+            if config.explicit_order(&path[1..]).is_some() {
+                let subcommands: Vec<_> = cmd
+                    .get_subcommands()
+                    .filter(|s| !config.is_skipped_subcommand(s))
+                    .map(|s| config.subcommand_name(s))
+                    .collect();
+                return check_subcommand_order(subcommands, path, config, out);
+            }
+            let subcommands = cmd
+                .get_subcommands()
+                .filter(|s| !config.is_skipped_subcommand(s));
+            for block in blocks(subcommands, config, |s| s.is_hide_set()) {
+                let subcommands: Vec<_> = block.iter().map(|s| config.subcommand_name(s)).collect();
+                check_subcommand_order(subcommands, path, config, out);
+            }
+        });
+    }
+
+    if config.kebab_case_subcommands
+        || config.max_subcommand_len.is_some()
+        || !config.allowed_verbs.is_empty()
+    {
+        run_rule("subcommand-names", out, |out| {
+            check_subcommand_names(cmd, path, config, out)
+        });
+    }
+
+    if config.alias_collisions {
+        run_rule("alias-collisions", out, |out| {
+            check_alias_collisions(cmd, path, out)
+        });
+    }
+
+    if config.alias_order {
+        run_rule("alias-order", out, |out| {
+            check_alias_order(cmd, path, config, out)
+        });
+    }
+
+    if config.builtin_shadowing {
+        run_rule("builtin-shadowing", out, |out| {
+            check_builtin_shadowing(cmd, path, out)
+        });
+    }
+
+    if !config.reserved_shorts.is_empty() {
+        run_rule("reserved-shorts", out, |out| {
+            check_reserved_shorts(cmd, path, config, out)
+        });
+    }
+
+    // Check arguments
+    run_rule("argument-order", out, |out| {
+        for block in blocks(cmd.get_arguments(), config, |a| a.is_hide_set()) {
+            check_argument_order(block, path, config, out);
+        }
+    });
+
+    if config.hidden_last {
+        run_rule("hidden-last", out, |out| {
+            check_hidden_last(cmd, path, config, out)
+        });
+    }
+
+    if config.contiguous_headings {
+        run_rule("contiguous-headings", out, |out| {
+            check_heading_contiguity(cmd, path, config, out)
+        });
+    }
+
+    if config.variadic_positionals {
+        run_rule("variadic-positionals", out, |out| {
+            check_variadic_positionals(cmd, path, out)
+        });
+    }
+
+    if config.positional_order {
+        run_rule("positional-order", out, |out| {
+            check_positional_order(cmd, path, config, out)
+        });
+    }
+
+    if let Some(style) = config.value_name_style {
+        run_rule("value-names", out, |out| {
+            check_value_names(cmd, path, style, out)
+        });
+    }
+
+    if config.possible_values {
+        run_rule("possible-values", out, |out| {
+            check_possible_values(cmd, path, config, out)
+        });
+    }
+
+    #[cfg(feature = "env")]
+    if let Some(pattern) = &config.env_pattern {
+        run_rule("env-names", out, |out| {
+            check_env_names(cmd, path, pattern, out)
+        });
+    }
+
+    // Check what users actually see
+    if config.rendered_help {
+        run_rule("rendered-help", out, |out| {
+            crate::help::check_rendered_help(cmd, path, config, out)
+        });
+    }
+}
+
+/// Runs one check, inside a `rule` span when tracing is enabled.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn run_rule(rule: &str, out: &mut Vec<Violation>, check: impl FnOnce(&mut Vec<Violation>)) {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("rule", rule).entered();
+    #[cfg(feature = "tracing")]
+    let (started, start) = (std::time::Instant::now(), out.len());
+
+    check(out);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        violations = out.len() - start,
+        elapsed_us = started.elapsed().as_micros() as u64,
+        "rule finished"
+    );
+}
+
+/// Splits `items` into the blocks that are sorted independently: visible
+/// then hidden with [`SortConfig::hidden_last`], otherwise a single block.
+fn blocks<T>(
+    items: impl Iterator<Item = T>,
+    config: &SortConfig,
+    is_hidden: impl Fn(&T) -> bool,
+) -> Vec<Vec<T>> {
+    if config.hidden_last {
+        let (visible, hidden) = items.partition(|item| !is_hidden(item));
+        vec![visible, hidden]
+    } else {
+        vec![items.collect()]
+    }
+}
+
+/// Checks that hidden subcommands and flags come after the visible ones.
+fn check_hidden_last(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let subcommands: Vec<(&str, bool)> = cmd
+        .get_subcommands()
+        .filter(|s| !config.is_skipped_subcommand(s))
+        .map(|s| (config.subcommand_name(s), s.is_hide_set()))
+        .filter(|(name, _)| !config.is_ignored_subcommand(name))
+        .collect();
+    check_hidden_block(&subcommands, "subcommands", path, config, out);
+
+    let flags: Vec<(String, bool)> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_positional() && !config.is_ignored_arg(a))
+        .map(|a| (flag_display(a), a.is_hide_set()))
+        .collect();
+    check_hidden_block(&flags, "flags", path, config, out);
+}
+
+/// Reports `items`, each a name and whether it is hidden, if a visible one
+/// follows a hidden one.
+fn check_hidden_block<S: AsRef<str>>(
+    items: &[(S, bool)],
+    what: &str,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let Some(first_hidden) = items.iter().position(|(_, hidden)| *hidden) else {
+        return;
+    };
+    if items[first_hidden..].iter().all(|(_, hidden)| *hidden) {
+        return;
+    }
+
+    let current: Vec<&str> = items.iter().map(|(name, _)| name.as_ref()).collect();
+    let (mut expected, hidden): (Vec<_>, Vec<_>) = items.iter().partition(|(_, hidden)| !hidden);
+    expected.extend(hidden);
+    let expected: Vec<&str> = expected.iter().map(|(name, _)| name.as_ref()).collect();
+
+    out.push(
+        Violation::new(
+            ViolationKind::HiddenNotLast,
+            path,
+            format!(
+                "Hidden {} in '{}' must come after visible ones!\n{}",
+                what,
+                path.join(" "),
+                order_details(
+                    &current,
+                    &expected,
+                    ("Actual", "Expected"),
+                    config.verbosity
+                )
+            ),
+        )
+        .with_order(&current, &expected),
+    );
+}
+
+/// Checks that arguments sharing a help heading are declared together.
+///
+/// Arguments without a heading are shown under "Arguments" or "Options",
+/// depending on whether they are positional, so they count as those
+/// headings.
+fn check_heading_contiguity(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let args: Vec<(String, &str)> = cmd
+        .get_arguments()
+        .filter(|a| !config.is_ignored_arg(a))
+        .map(|a| {
+            let heading = a.get_help_heading().unwrap_or(if a.is_positional() {
+                "Arguments"
+            } else {
+                "Options"
+            });
+            (flag_display(a), heading)
+        })
+        .collect();
+
+    let mut headings: Vec<&str> = Vec::new();
+    let mut scattered: Vec<&str> = Vec::new();
+    for (i, (_, heading)) in args.iter().enumerate() {
+        if !headings.contains(heading) {
+            headings.push(heading);
+        } else if args[i - 1].1 != *heading && !scattered.contains(heading) {
+            scattered.push(heading);
+        }
+    }
+    if scattered.is_empty() {
+        return;
+    }
+
+    let current: Vec<&str> = args.iter().map(|(name, _)| name.as_str()).collect();
+    let expected: Vec<&str> = headings
+        .iter()
+        .flat_map(|heading| {
+            args.iter()
+                .filter(move |(_, h)| h == heading)
+                .map(|(name, _)| name.as_str())
+        })
+        .collect();
+
+    out.push(
+        Violation::new(
+            ViolationKind::ScatteredHelpHeading,
+            path,
+            format!(
+                "Arguments under {} in '{}' are not declared together!\n{}",
+                scattered
+                    .iter()
+                    .map(|h| format!("'{}'", h))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                path.join(" "),
+                order_details(
+                    &current,
+                    &expected,
+                    ("Actual", "Expected"),
+                    config.verbosity
+                )
+            ),
+        )
+        .with_order(&current, &expected),
+    );
+}
+
+/// Checks that no positional argument can take the name of an external
+/// subcommand.
+///
+/// clap fills positionals first: an optional one takes the name when it is
+/// left out, and one taking multiple values takes every word after it.
+/// Required single-value positionals are always filled first, so they are
+/// fine.
+fn check_external_subcommands(cmd: &clap::Command, path: &[&str], out: &mut Vec<Violation>) {
+    let positionals: Vec<_> = cmd
+        .get_positionals()
+        .filter(|a| !a.is_required_set() || takes_multiple(a))
+        .map(|a| a.get_id().to_string())
+        .collect();
+
+    if !positionals.is_empty() {
+        out.push(Violation::new(
+            ViolationKind::ShadowedExternalSubcommands,
+            path,
+            format!(
+                "Command '{}' allows external subcommands but declares optional or variadic positional arguments {:?}, which can take the external subcommand's name!",
+                path.join(" "),
+                positionals
+            ),
+        ));
+    }
+}
+
+/// Checks a list of subcommand names in the order they are declared or shown.
+///
+/// Pinned subcommands are left out of the alphabetical check and validated
+/// separately against their pinned positions.
+pub(crate) fn check_subcommand_order(
+    subcommands: Vec<&str>,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    if let Some(expected) = config.explicit_order(&path[1..]) {
+        if subcommands.iter().ne(expected) {
+            let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+            out.push(
+                Violation::new(
+                    ViolationKind::ExplicitOrder,
+                    path,
+                    format!(
+                        "Subcommands in '{}' don't match the expected order!\n{}",
+                        path.join(" "),
+                        order_details(
+                            &subcommands,
+                            &expected,
+                            ("Actual order", "Expected order"),
+                            config.verbosity,
+                        )
+                    ),
+                )
+                .with_order(&subcommands, &expected),
+            );
+        }
+        return;
+    }
+
+    let subcommands: Vec<_> = subcommands
+        .into_iter()
+        .filter(|name| !config.is_ignored_subcommand(name))
+        .collect();
+
+    let unpinned: Vec<&str> = subcommands
+        .iter()
+        .copied()
+        .filter(|name| !config.is_pinned(name))
+        .collect();
+    let mut sorted = unpinned.clone();
+    sorted.sort_by(|a, b| config.compare_subcommands(a, b));
+
+    if unpinned != sorted {
+        out.push(
+            Violation::new(
+                ViolationKind::UnsortedSubcommands,
+                path,
+                format!(
+                    "Subcommands in '{}' are not sorted{}!\n{}",
+                    path.join(" "),
+                    // A custom comparator may not be alphabetical
+                    if config.subcommand_comparator.is_some() {
+                        ""
+                    } else {
+                        " alphabetically"
+                    },
+                    order_details(
+                        &unpinned,
+                        &sorted,
+                        ("Actual order", "Expected order"),
+                        config.verbosity,
+                    )
+                ),
+            )
+            .with_order(&unpinned, &sorted),
+        );
+    }
+
+    let present = |pins: &[String]| -> Vec<&str> {
+        pins.iter()
+            .filter_map(|pin| subcommands.iter().copied().find(|name| name == pin))
+            .collect()
+    };
+    let mut expected = present(&config.pinned_first);
+    expected.extend(&unpinned);
+    expected.extend(present(&config.pinned_last));
+
+    if subcommands != expected {
+        out.push(
+            Violation::new(
+                ViolationKind::PinnedSubcommands,
+                path,
+                format!(
+                    "Pinned subcommands in '{}' are out of place!\n{}",
+                    path.join(" "),
+                    order_details(
+                        &subcommands,
+                        &expected,
+                        ("Actual order", "Expected order"),
+                        config.verbosity,
+                    )
+                ),
+            )
+            .with_order(&subcommands, &expected),
+        );
+    }
+}
+
+/// Checks that subcommand names follow the configured naming rules.
+fn check_subcommand_names(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    for subcmd in cmd.get_subcommands() {
+        let name = subcmd.get_name();
+        let mut subcmd_path = path[1..].to_vec();
+        subcmd_path.push(name);
+        if config.is_allowed_subcommand_name(&subcmd_path) {
+            continue;
+        }
+
+        if config.kebab_case_subcommands && !ValueNameStyle::KebabCase.matches(name) {
+            out.push(Violation::new(
+                ViolationKind::SubcommandName,
+                path,
+                format!(
+                    "Subcommand '{}' in '{}' is not lowercase kebab-case!",
+                    name,
+                    path.join(" ")
+                ),
+            ));
+        }
+        if let Some(max) = config.max_subcommand_len
+            && name.chars().count() > max
+        {
+            out.push(Violation::new(
+                ViolationKind::SubcommandName,
+                path,
+                format!(
+                    "Subcommand '{}' in '{}' is longer than {} characters!",
+                    name,
+                    path.join(" "),
+                    max
+                ),
+            ));
+        }
+
+        let verb = name.split('-').next().unwrap_or(name);
+        if !config.allowed_verbs.is_empty()
+            && !subcmd.has_subcommands()
+            && !config.allowed_verbs.iter().any(|v| v == verb)
+        {
+            out.push(Violation::new(
+                ViolationKind::SubcommandVerb,
+                path,
+                format!(
+                    "Subcommand '{}' in '{}' does not start with an allowed verb!\nAllowed: {:?}",
+                    name,
+                    path.join(" "),
+                    config.allowed_verbs
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks that no two subcommands share a name or alias.
+fn check_alias_collisions(cmd: &clap::Command, path: &[&str], out: &mut Vec<Violation>) {
+    let mut seen: Vec<(&str, &str)> = Vec::new();
+
+    for subcmd in cmd.get_subcommands() {
+        let owner = subcmd.get_name();
+        for name in std::iter::once(owner).chain(subcmd.get_all_aliases()) {
+            match seen.iter().find(|(seen_name, _)| *seen_name == name) {
+                Some((_, other)) if *other != owner => {
+                    out.push(Violation::new(
+                        ViolationKind::AliasCollision,
+                        path,
+                        format!(
+                            "Subcommand name or alias '{}' in '{}' is used by both '{}' and '{}'!",
+                            name,
+                            path.join(" "),
+                            other,
+                            owner
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                None => seen.push((name, owner)),
+            }
+        }
+    }
+}
+
+/// Checks that each subcommand and flag lists its aliases in sorted order.
+fn check_alias_order(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    for subcmd in cmd.get_subcommands() {
+        let aliases: Vec<&str> = subcmd.get_all_aliases().collect();
+        let mut sorted = aliases.clone();
+        sorted.sort_by(|a, b| config.compare_names(a, b, config.subcommand_direction));
+
+        if aliases != sorted {
+            out.push(
+                Violation::new(
+                    ViolationKind::UnsortedAliases,
+                    path,
+                    format!(
+                        "Aliases of '{}' in '{}' are not sorted!\n{}",
+                        subcmd.get_name(),
+                        path.join(" "),
+                        order_details(&aliases, &sorted, ("Actual", "Expected"), config.verbosity)
+                    ),
+                )
+                .with_order(&aliases, &sorted),
+            );
+        }
+    }
+
+    for arg in cmd.get_arguments() {
+        if config.is_ignored_arg(arg) {
+            continue;
+        }
+        let aliases: Vec<String> = arg
+            .get_all_aliases()
+            .unwrap_or_default()
+            .iter()
+            .map(|alias| format!("--{}", alias))
+            .collect();
+        let mut sorted = aliases.clone();
+        sorted.sort_by(|a, b| config.compare_long_flags(&a[2..], &b[2..]));
+
+        if aliases != sorted {
+            out.push(
+                Violation::new(
+                    ViolationKind::UnsortedAliases,
+                    path,
+                    format!(
+                        "Aliases of '{}' in '{}' are not sorted!\n{}",
+                        flag_display(arg),
+                        path.join(" "),
+                        order_details(&aliases, &sorted, ("Actual", "Expected"), config.verbosity)
+                    ),
+                )
+                .with_order(&aliases, &sorted),
+            );
+        }
+    }
+}
+
+/// Checks that no flag takes the names of a help or version flag that clap
+/// will generate.
+///
+/// clap refuses to build such a command, but only once it is built, and
+/// only in debug builds.
+fn check_builtin_shadowing(cmd: &clap::Command, path: &[&str], out: &mut Vec<Violation>) {
+    let generates_help = !cmd.is_disable_help_flag_set();
+    let generates_version = !cmd.is_disable_version_flag_set()
+        && (cmd.get_version().is_some() || cmd.get_long_version().is_some());
+
+    for arg in cmd.get_arguments().filter(|a| !is_builtin_flag(a)) {
+        let shadowed = [
+            (generates_help, 'h', "help", "disable_help_flag"),
+            (generates_version, 'V', "version", "disable_version_flag"),
+        ];
+        for (generated, short, long, disable) in shadowed {
+            let name = if arg.get_short() == Some(short) {
+                format!("-{}", short)
+            } else if arg.get_long() == Some(long) {
+                format!("--{}", long)
+            } else {
+                continue;
+            };
+            if generated {
+                out.push(Violation::new(
+                    ViolationKind::ShadowedBuiltinFlag,
+                    path,
+                    format!(
+                        "Argument '{}' in '{}' uses '{}', which clap also gives its {} flag! Call `{}(true)` if this is intended.",
+                        arg.get_id(),
+                        path.join(" "),
+                        name,
+                        long,
+                        disable
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Checks that reserved short flags are only used by the arguments they are
+/// reserved for.
+fn check_reserved_shorts(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    for arg in cmd.get_arguments() {
+        let Some(short) = arg.get_short() else {
+            continue;
+        };
+        let Some((_, id)) = config.reserved_shorts.iter().find(|(s, _)| *s == short) else {
+            continue;
+        };
+        if arg.get_id() != id.as_str() {
+            out.push(Violation::new(
+                ViolationKind::ReservedShort,
+                path,
+                format!(
+                    "Argument '{}' in '{}' uses '-{}', which is reserved for '{}'!",
+                    arg.get_id(),
+                    path.join(" "),
+                    short,
+                    id
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks that only the last positional argument takes multiple values.
+///
+/// Like clap, this allows an earlier one when the last positional is
+/// required or marked `last`, or when the earlier one has a value
+/// terminator, since the boundary between them is unambiguous then.
+fn check_variadic_positionals(cmd: &clap::Command, path: &[&str], out: &mut Vec<Violation>) {
+    let mut positionals: Vec<_> = cmd.get_positionals().collect();
+    positionals.sort_by_key(|a| a.get_index().unwrap_or(usize::MAX));
+    let Some((last, leading)) = positionals.split_last() else {
+        return;
+    };
+    if last.is_required_set() || last.is_last_set() {
+        return;
+    }
+
+    for arg in leading {
+        if takes_multiple(arg) && arg.get_value_terminator().is_none() {
+            out.push(Violation::new(
+                ViolationKind::VariadicPositional,
+                path,
+                format!(
+                    "Positional argument '{}' in '{}' takes multiple values but is not the last positional!",
+                    arg.get_id(),
+                    path.join(" ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks that explicit value names follow `style`.
+fn check_value_names(
+    cmd: &clap::Command,
+    path: &[&str],
+    style: ValueNameStyle,
+    out: &mut Vec<Violation>,
+) {
+    for arg in cmd.get_arguments() {
+        let names = arg.get_value_names().unwrap_or_default();
+        if let Some(name) = names.iter().find(|name| !style.matches(name)) {
+            out.push(Violation::new(
+                ViolationKind::ValueName,
+                path,
+                format!(
+                    "Value name '{}' of '{}' in '{}' is not {:?}!",
+                    name,
+                    arg.get_id(),
+                    path.join(" "),
+                    style
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks that required positionals come first, then optional ones, then
+/// those taking multiple values or marked `last`.
+fn check_positional_order(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let mut positionals: Vec<_> = cmd.get_positionals().collect();
+    positionals.sort_by_key(|a| a.get_index().unwrap_or(usize::MAX));
+
+    let rank = |arg: &clap::Arg| {
+        if arg.is_last_set() || takes_multiple(arg) {
+            2
+        } else if arg.is_required_set() {
+            0
+        } else {
+            1
+        }
+    };
+    if positionals.is_sorted_by_key(|a| rank(a)) {
+        return;
+    }
+
+    let current: Vec<&str> = positionals.iter().map(|a| a.get_id().as_str()).collect();
+    let mut expected = positionals.clone();
+    expected.sort_by_key(|a| rank(a));
+    let expected: Vec<&str> = expected.iter().map(|a| a.get_id().as_str()).collect();
+
+    out.push(
+        Violation::new(
+            ViolationKind::PositionalOrder,
+            path,
+            format!(
+                "Positional arguments in '{}' must be required, then optional, then variadic or `last` ones!\n{}",
+                path.join(" "),
+                order_details(&current, &expected, ("Actual", "Expected"), config.verbosity)
+            ),
+        )
+        .with_order(&current, &expected),
+    );
+}
+
+/// Returns true if `arg` accepts more than one value.
+fn takes_multiple(arg: &clap::Arg) -> bool {
+    matches!(arg.get_action(), clap::ArgAction::Append)
+        || arg.get_num_args().is_some_and(|n| n.max_values() > 1)
+}
+
+/// Checks that the possible values of each argument are sorted.
+fn check_possible_values(
+    cmd: &clap::Command,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    for arg in cmd.get_arguments() {
+        if config.is_ignored_arg(arg) {
+            continue;
+        }
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .map(|value| value.get_name().to_string())
+            .collect();
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| config.compare_names(a, b, Direction::Ascending));
+
+        if values != sorted {
+            out.push(
+                Violation::new(
+                    ViolationKind::UnsortedPossibleValues,
+                    path,
+                    format!(
+                        "Possible values of '{}' in '{}' are not sorted!\n{}",
+                        flag_display(arg),
+                        path.join(" "),
+                        order_details(&values, &sorted, ("Actual", "Expected"), config.verbosity)
+                    ),
+                )
+                .with_order(&values, &sorted),
+            );
+        }
+    }
+}
+
+/// Checks that environment variable names follow `pattern`.
+#[cfg(feature = "env")]
+fn check_env_names(cmd: &clap::Command, path: &[&str], pattern: &str, out: &mut Vec<Violation>) {
+    for arg in cmd.get_arguments() {
+        let Some(env) = arg.get_env() else {
+            continue;
+        };
+        let id = arg.get_id().as_str().to_uppercase().replace('-', "_");
+        let expected = pattern.replace("{ID}", &id);
+
+        if env != expected.as_str() {
+            out.push(Violation::new(
+                ViolationKind::EnvName,
+                path,
+                format!(
+                    "Environment variable for '{}' in '{}' is {:?}, expected {:?}!",
+                    arg.get_id(),
+                    path.join(" "),
+                    env,
+                    expected
+                ),
+            ));
+        }
+    }
+}
+
+/// Checks a list of arguments in the order they are declared or shown.
+pub(crate) fn check_argument_order(
+    args: Vec<&clap::Arg>,
+    path: &[&str],
+    config: &SortConfig,
+    out: &mut Vec<Violation>,
+) {
+    let args: Vec<_> = args
+        .into_iter()
+        .filter(|arg| !config.is_ignored_arg(arg))
+        .collect();
+
+    let mut positional = Vec::new();
+    let mut with_short = Vec::new();
+    let mut long_only = Vec::new();
+
+    for arg in &args {
+        if is_builtin_flag(arg) {
+            continue;
+        } else if arg.is_positional() {
+            positional.push(*arg);
+        } else if arg.get_short().is_some() {
+            with_short.push(*arg);
+        } else if arg.get_long().is_some() {
+            long_only.push(*arg);
+        }
+    }
+
+    // Note: We don't check if positional args are sorted - their order matters for parsing
+
+    // Check short flags are sorted by short option
+    let mut sorted_args = with_short.clone();
+    sorted_args.sort_by(|a, b| config.compare_short_flags(a, b));
+
+    if config.short_flags
+        && with_short
+            .iter()
+            .zip(&sorted_args)
+            .any(|(a, b)| a.get_id() != b.get_id())
+    {
+        let current: Vec<String> = with_short.iter().map(|a| short_label(a, config)).collect();
+        let expected: Vec<String> = sorted_args.iter().map(|a| short_label(a, config)).collect();
+
+        out.push(
+            Violation::new(
+                ViolationKind::UnsortedShortFlags,
+                path,
+                format!(
+                    "Flags with short options in '{}' are not sorted!\n{}",
+                    path.join(" "),
+                    order_details(
+                        &current,
+                        &expected,
+                        ("Actual", "Expected"),
+                        config.verbosity,
+                    )
+                ),
+            )
+            .with_order(&current, &expected),
+        );
+    }
+
+    // Check long-only flags are sorted
+    let long_only_longs: Vec<&str> = long_only.iter().filter_map(|a| a.get_long()).collect();
+    let mut sorted_longs = long_only_longs.clone();
+    sorted_longs.sort_by(|a, b| config.compare_long_flags(a, b));
+
+    if config.long_flags && long_only_longs != sorted_longs {
+        let current: Vec<String> = long_only_longs.iter().map(|l| format!("--{}", l)).collect();
+        let expected: Vec<String> = sorted_longs.iter().map(|l| format!("--{}", l)).collect();
+
+        out.push(
+            Violation::new(
+                ViolationKind::UnsortedLongFlags,
+                path,
+                format!(
+                    "Long-only flags in '{}' are not sorted!\n{}",
+                    path.join(" "),
+                    order_details(
+                        &current,
+                        &expected,
+                        ("Actual", "Expected"),
+                        config.verbosity,
+                    )
+                ),
+            )
+            .with_order(&current, &expected),
+        );
+    }
+
+    // Check help/version flags come after every other flag
+    if config.builtin_flags == BuiltinFlags::Last {
+        let flags: Vec<&clap::Arg> = args
+            .iter()
+            .copied()
+            .filter(|a| !a.is_positional())
+            .collect();
+        let (mut others, builtins): (Vec<&clap::Arg>, Vec<&clap::Arg>) =
+            flags.iter().partition(|a| !is_builtin_flag(a));
+
+        if let Some(first_builtin) = flags.iter().position(|a| is_builtin_flag(a))
+            && flags[first_builtin..].iter().any(|a| !is_builtin_flag(a))
+        {
+            let current: Vec<String> = flags.iter().map(|a| flag_display(a)).collect();
+            others.extend(builtins);
+            let expected: Vec<String> = others.iter().map(|a| flag_display(a)).collect();
+
+            out.push(
+                Violation::new(
+                    ViolationKind::BuiltinFlagsNotLast,
+                    path,
+                    format!(
+                        "Help and version flags in '{}' must come last!\n{}",
+                        path.join(" "),
+                        order_details(
+                            &current,
+                            &expected,
+                            ("Actual", "Expected"),
+                            config.verbosity,
+                        )
+                    ),
+                )
+                .with_order(&current, &expected),
+            );
+        }
+    }
+
+    // Group order is opt-in: flattened structs can cause positionals and
+    // flags to be interspersed, which is valid for clap but would fail a
+    // strict group order check.
+    if config.group_order {
+        let grouped: Vec<&clap::Arg> = args
+            .iter()
+            .copied()
+            .filter(|a| !is_builtin_flag(a) && arg_group(a).is_some())
+            .collect();
+
+        if !grouped.is_sorted_by_key(|a| arg_group(a)) {
+            let current: Vec<String> = grouped.iter().map(|a| flag_display(a)).collect();
+            let mut expected = grouped.clone();
+            expected.sort_by_key(|a| arg_group(a));
+            let expected: Vec<String> = expected.iter().map(|a| flag_display(a)).collect();
+
+            out.push(Violation::new(
+                ViolationKind::WrongGroupOrder,
+                path,
+                format!(
+                    "Arguments in '{}' must be positionals, then flags with short options, then long-only flags!\n{}",
+                    path.join(" "),
+                    order_details(
+                        &current,
+                        &expected,
+                        ("Actual", "Expected"),
+                        config.verbosity,
+                    )
+                ),
+            )
+            .with_order(&current, &expected));
+        }
+    }
+}
+
+/// The group an argument is sorted within: positionals, flags with short
+/// options, or long-only flags. Other arguments belong to none.
+pub(crate) fn arg_group(arg: &clap::Arg) -> Option<u8> {
+    if arg.is_positional() {
+        Some(0)
+    } else if arg.get_short().is_some() {
+        Some(1)
+    } else if arg.get_long().is_some() {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Returns true for help and version flags, whether clap generated them or not.
+pub(crate) fn is_builtin_flag(arg: &clap::Arg) -> bool {
+    matches!(
+        arg.get_action(),
+        clap::ArgAction::Help
+            | clap::ArgAction::HelpShort
+            | clap::ArgAction::HelpLong
+            | clap::ArgAction::Version
+    )
+}
+
+/// Formats a flag the way it is most recognizable in help output.
+pub(crate) fn flag_display(arg: &clap::Arg) -> String {
+    match (arg.get_short(), arg.get_long()) {
+        (Some(short), _) => format!("-{}", short),
+        (None, Some(long)) => format!("--{}", long),
+        (None, None) => arg.get_id().to_string(),
+    }
+}
+
+/// Formats a short flag along with whatever else it may be sorted by: its
+/// short aliases, and its long flag with [`ShortKey::Long`].
+fn short_label(arg: &clap::Arg, config: &SortConfig) -> String {
+    let mut extra = Vec::new();
+    if config.short_key == ShortKey::Long
+        && let Some(long) = arg.get_long()
+    {
+        extra.push(format!("--{}", long));
+    }
+    if let Some(aliases) = arg.get_all_short_aliases()
+        && !aliases.is_empty()
+    {
+        let aliases: Vec<String> = aliases.iter().map(|a| format!("-{}", a)).collect();
+        extra.push(format!("aliases: {}", aliases.join(", ")));
+    }
+
+    let short = format!("-{}", arg.get_short().unwrap_or_default());
+    if extra.is_empty() {
+        short
+    } else {
+        format!("{} ({})", short, extra.join(", "))
+    }
+}
@@ -0,0 +1,168 @@
+//! Runtime transforms that make a command display its items sorted.
+//!
+//! Each subcommand or argument gets the `display_order` of its sorted
+//! position. The transforms visit items through clap's `mut_subcommand` and
+//! `mut_arg`, which move the visited item to the end, so visiting them in
+//! sorted order also leaves `get_subcommands()` and `get_arguments()` sorted.
+
+use crate::SortConfig;
+use crate::fix::{arg_order, subcommand_order};
+
+/// Sorts the subcommands of `cmd` and all nested commands.
+pub(crate) fn sort_subcommands(cmd: clap::Command, config: &SortConfig) -> clap::Command {
+    transform(cmd, config, true, false, &[])
+}
+
+/// Sorts the arguments of `cmd` and all nested commands.
+pub(crate) fn sort_args(cmd: clap::Command, config: &SortConfig) -> clap::Command {
+    transform(cmd, config, false, true, &[])
+}
+
+/// Sorts both the subcommands and arguments of `cmd` and all nested commands.
+pub(crate) fn sort_command(cmd: clap::Command, config: &SortConfig) -> clap::Command {
+    transform(cmd, config, true, true, &[])
+}
+
+fn transform(
+    mut cmd: clap::Command,
+    root_config: &SortConfig,
+    subcommands: bool,
+    args: bool,
+    parent_path: &[String],
+) -> clap::Command {
+    let config = root_config.for_depth(parent_path.len());
+    let mut path = parent_path.to_vec();
+    path.push(cmd.get_name().to_string());
+
+    if args {
+        let ids: Vec<String> = cmd
+            .get_arguments()
+            .map(|a| a.get_id().to_string())
+            .collect();
+        for (position, index) in arg_order(&cmd, config).into_iter().enumerate() {
+            cmd = cmd.mut_arg(&ids[index], |a| {
+                if a.is_positional() {
+                    a
+                } else {
+                    a.display_order(position)
+                }
+            });
+        }
+    }
+
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|s| s.get_name().to_string())
+        .collect();
+    let order = if subcommands {
+        subcommand_order(&cmd, &path, config)
+    } else {
+        (0..names.len()).collect()
+    };
+    // Multicall applets are each their own root
+    if cmd.is_multicall_set() {
+        path.clear();
+    }
+
+    for (position, index) in order.into_iter().enumerate() {
+        cmd = cmd.mut_subcommand(&names[index], |s| {
+            let s = transform(s, root_config, subcommands, args, &path);
+            if subcommands {
+                s.display_order(position)
+            } else {
+                s
+            }
+        });
+    }
+
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, Command};
+
+    fn rendered_order(cmd: &mut Command, heading: &str) -> Vec<String> {
+        let help = cmd.render_help().to_string();
+        help.lines()
+            .skip_while(|line| *line != format!("{}:", heading))
+            .skip(1)
+            .take_while(|line| line.starts_with(' '))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|token| token.trim_end_matches(',').to_string())
+            .collect()
+    }
+
+    fn unsorted() -> Command {
+        Command::new("test")
+            .arg(Arg::new("verbose").short('v').long("verbose"))
+            .arg(Arg::new("debug").short('d').long("debug"))
+            .subcommand(Command::new("list"))
+            .subcommand(
+                Command::new("add")
+                    .subcommand(Command::new("zebra"))
+                    .subcommand(Command::new("apple")),
+            )
+    }
+
+    #[test]
+    fn test_sort_command() {
+        let mut cmd = sort_command(unsorted(), &SortConfig::default());
+
+        assert_eq!(
+            rendered_order(&mut cmd, "Commands"),
+            ["add", "list", "help"]
+        );
+        assert_eq!(rendered_order(&mut cmd, "Options"), ["-d", "-v", "-h"]);
+
+        let mut add = cmd.find_subcommand("add").unwrap().clone();
+        assert_eq!(
+            rendered_order(&mut add, "Commands"),
+            ["apple", "zebra", "help"]
+        );
+    }
+
+    #[test]
+    fn test_sort_subcommands_only() {
+        let mut cmd = sort_subcommands(unsorted(), &SortConfig::default());
+
+        assert_eq!(
+            rendered_order(&mut cmd, "Commands"),
+            ["add", "list", "help"]
+        );
+        assert_eq!(rendered_order(&mut cmd, "Options"), ["-v", "-d", "-h"]);
+    }
+
+    #[test]
+    fn test_sort_args_only() {
+        let mut cmd = sort_args(unsorted(), &SortConfig::default());
+
+        assert_eq!(
+            rendered_order(&mut cmd, "Commands"),
+            ["list", "add", "help"]
+        );
+        assert_eq!(rendered_order(&mut cmd, "Options"), ["-d", "-v", "-h"]);
+    }
+
+    #[test]
+    fn test_sort_command_reorders_declarations() {
+        let cmd = sort_command(unsorted(), &SortConfig::default());
+
+        let names: Vec<&str> = cmd.get_subcommands().map(|s| s.get_name()).collect();
+        assert_eq!(names, ["add", "list"]);
+        let ids: Vec<&str> = cmd.get_arguments().map(|a| a.get_id().as_str()).collect();
+        assert_eq!(ids, ["debug", "verbose"]);
+        assert!(crate::is_sorted(&cmd).is_ok());
+    }
+
+    #[test]
+    fn test_sorted_help_passes_rendered_check() {
+        let cmd = sort_command(unsorted(), &SortConfig::default());
+        let config = SortConfig::new().rendered_help(true);
+
+        let mut out = Vec::new();
+        crate::help::check_rendered_help(&cmd, &["test"], &config, &mut out);
+        assert!(out.is_empty(), "{:?}", out);
+    }
+}
@@ -0,0 +1,256 @@
+use std::fmt;
+
+/// How serious a violation is.
+///
+/// Only errors make [`is_sorted`](crate::is_sorted) and the assertions fail;
+/// warnings are reported by [`check_all`](crate::check_all) alongside them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Severity {
+    /// The violation fails validation.
+    #[default]
+    Error,
+    /// The violation is reported but does not fail validation.
+    Warning,
+}
+
+/// The rule a [`Violation`] breaks.
+///
+/// Variants are declared in the order the rules run on each command, which
+/// is also the order violations of one command are reported in.
+///
+/// Each kind also has a stable [`code`](Self::code) such as `CS0002`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// Positional arguments shadow external subcommands.
+    ShadowedExternalSubcommands,
+    /// Subcommands are not in alphabetical (or configured) order.
+    UnsortedSubcommands,
+    /// Subcommands do not match an explicitly configured order.
+    ExplicitOrder,
+    /// Pinned subcommands are not at their pinned positions.
+    PinnedSubcommands,
+    /// A subcommand name breaks the naming rules.
+    SubcommandName,
+    /// A subcommand name does not start with an allowed verb.
+    SubcommandVerb,
+    /// Two subcommands share a name or alias.
+    AliasCollision,
+    /// Aliases are not declared in sorted order.
+    UnsortedAliases,
+    /// A flag takes the short or long name of clap's generated help or
+    /// version flag.
+    ShadowedBuiltinFlag,
+    /// A reserved short flag is used by an argument other than the one it
+    /// is reserved for.
+    ReservedShort,
+    /// Flags with short options are not sorted.
+    UnsortedShortFlags,
+    /// Long-only flags are not sorted.
+    UnsortedLongFlags,
+    /// Help or version flags are followed by other flags.
+    BuiltinFlagsNotLast,
+    /// Positionals, short flags and long-only flags are not declared in
+    /// that order.
+    WrongGroupOrder,
+    /// Hidden subcommands or flags are followed by visible ones.
+    HiddenNotLast,
+    /// Arguments sharing a help heading are not declared together.
+    ScatteredHelpHeading,
+    /// A positional other than the last takes multiple values.
+    VariadicPositional,
+    /// Positionals are not ordered required, then optional, then variadic.
+    PositionalOrder,
+    /// A value name does not follow the configured style.
+    ValueName,
+    /// An argument's possible values are not sorted.
+    UnsortedPossibleValues,
+    /// An environment variable name does not follow the configured pattern.
+    EnvName,
+    /// The rendered help lists items out of order.
+    RenderedHelpOrder,
+    /// Commands disagree on whether help is shown in declaration order.
+    InconsistentDisplayOrder,
+}
+
+impl ViolationKind {
+    /// Every kind, in declaration order.
+    pub(crate) const ALL: &'static [ViolationKind] = &[
+        ViolationKind::ShadowedExternalSubcommands,
+        ViolationKind::UnsortedSubcommands,
+        ViolationKind::ExplicitOrder,
+        ViolationKind::PinnedSubcommands,
+        ViolationKind::SubcommandName,
+        ViolationKind::SubcommandVerb,
+        ViolationKind::AliasCollision,
+        ViolationKind::UnsortedAliases,
+        ViolationKind::ShadowedBuiltinFlag,
+        ViolationKind::ReservedShort,
+        ViolationKind::UnsortedShortFlags,
+        ViolationKind::UnsortedLongFlags,
+        ViolationKind::BuiltinFlagsNotLast,
+        ViolationKind::WrongGroupOrder,
+        ViolationKind::HiddenNotLast,
+        ViolationKind::ScatteredHelpHeading,
+        ViolationKind::VariadicPositional,
+        ViolationKind::PositionalOrder,
+        ViolationKind::ValueName,
+        ViolationKind::UnsortedPossibleValues,
+        ViolationKind::EnvName,
+        ViolationKind::RenderedHelpOrder,
+        ViolationKind::InconsistentDisplayOrder,
+    ];
+
+    /// Kebab-case name used to refer to the rule in config files and
+    /// environment overrides.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ViolationKind::ShadowedExternalSubcommands => "shadowed-external-subcommands",
+            ViolationKind::UnsortedSubcommands => "unsorted-subcommands",
+            ViolationKind::ExplicitOrder => "explicit-order",
+            ViolationKind::PinnedSubcommands => "pinned-subcommands",
+            ViolationKind::SubcommandName => "subcommand-name",
+            ViolationKind::SubcommandVerb => "subcommand-verb",
+            ViolationKind::AliasCollision => "alias-collision",
+            ViolationKind::UnsortedAliases => "unsorted-aliases",
+            ViolationKind::ShadowedBuiltinFlag => "shadowed-builtin-flag",
+            ViolationKind::ReservedShort => "reserved-short",
+            ViolationKind::UnsortedShortFlags => "unsorted-short-flags",
+            ViolationKind::UnsortedLongFlags => "unsorted-long-flags",
+            ViolationKind::BuiltinFlagsNotLast => "builtin-flags-not-last",
+            ViolationKind::WrongGroupOrder => "wrong-group-order",
+            ViolationKind::HiddenNotLast => "hidden-not-last",
+            ViolationKind::ScatteredHelpHeading => "scattered-help-heading",
+            ViolationKind::VariadicPositional => "variadic-positional",
+            ViolationKind::PositionalOrder => "positional-order",
+            ViolationKind::ValueName => "value-name",
+            ViolationKind::UnsortedPossibleValues => "unsorted-possible-values",
+            ViolationKind::EnvName => "env-name",
+            ViolationKind::RenderedHelpOrder => "rendered-help-order",
+            ViolationKind::InconsistentDisplayOrder => "inconsistent-display-order",
+        }
+    }
+
+    /// A stable identifier for the rule, for tools that track violations
+    /// across releases, such as dashboards and baselines.
+    ///
+    /// Unlike messages, codes never change once released: a code is not
+    /// reused or reassigned, and new kinds get the next free number. Codes
+    /// are accepted wherever config files and environment overrides take a
+    /// rule name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clap_sort::ViolationKind;
+    ///
+    /// assert_eq!(ViolationKind::UnsortedSubcommands.code(), "CS0002");
+    /// ```
+    pub fn code(self) -> &'static str {
+        match self {
+            ViolationKind::ShadowedExternalSubcommands => "CS0001",
+            ViolationKind::UnsortedSubcommands => "CS0002",
+            ViolationKind::PinnedSubcommands => "CS0003",
+            ViolationKind::SubcommandName => "CS0004",
+            ViolationKind::SubcommandVerb => "CS0005",
+            ViolationKind::AliasCollision => "CS0006",
+            ViolationKind::UnsortedShortFlags => "CS0007",
+            ViolationKind::UnsortedLongFlags => "CS0008",
+            ViolationKind::BuiltinFlagsNotLast => "CS0009",
+            ViolationKind::VariadicPositional => "CS0010",
+            ViolationKind::ValueName => "CS0011",
+            ViolationKind::EnvName => "CS0012",
+            ViolationKind::RenderedHelpOrder => "CS0013",
+            ViolationKind::InconsistentDisplayOrder => "CS0014",
+            ViolationKind::HiddenNotLast => "CS0015",
+            ViolationKind::ShadowedBuiltinFlag => "CS0016",
+            ViolationKind::ReservedShort => "CS0017",
+            ViolationKind::ScatteredHelpHeading => "CS0018",
+            ViolationKind::ExplicitOrder => "CS0019",
+            ViolationKind::WrongGroupOrder => "CS0020",
+            ViolationKind::UnsortedAliases => "CS0021",
+            ViolationKind::UnsortedPossibleValues => "CS0022",
+            ViolationKind::PositionalOrder => "CS0023",
+        }
+    }
+
+    /// Looks up a kind by its [`name`](Self::name) or [`code`](Self::code).
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name || kind.code() == name)
+    }
+}
+
+/// A single problem found in a command tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Full command path from the root, e.g. `["mycli", "generate"]`.
+    pub path: Vec<String>,
+    /// The rule that was broken.
+    pub kind: ViolationKind,
+    /// Whether the violation fails validation.
+    pub severity: Severity,
+    /// Human-readable description, including actual and expected order
+    /// where that applies.
+    pub message: String,
+    /// The items involved, in their current order, for violations about
+    /// ordering. Empty for other kinds.
+    pub actual: Vec<String>,
+    /// The same items in the order they should be in. Empty for violations
+    /// that are not about ordering.
+    pub expected: Vec<String>,
+}
+
+impl Violation {
+    pub(crate) fn new(kind: ViolationKind, path: &[&str], message: String) -> Self {
+        Self {
+            path: path.iter().map(|p| p.to_string()).collect(),
+            kind,
+            severity: Severity::Error,
+            message,
+            actual: Vec::new(),
+            expected: Vec::new(),
+        }
+    }
+
+    /// Records the current and expected order of the items involved.
+    pub(crate) fn with_order<S: AsRef<str>>(mut self, actual: &[S], expected: &[S]) -> Self {
+        self.actual = actual.iter().map(|s| s.as_ref().to_string()).collect();
+        self.expected = expected.iter().map(|s| s.as_ref().to_string()).collect();
+        self
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let mut codes: Vec<&str> = ViolationKind::ALL.iter().map(|k| k.code()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ViolationKind::ALL.len());
+    }
+
+    #[test]
+    fn test_from_name_accepts_codes() {
+        assert_eq!(
+            ViolationKind::from_name("CS0008"),
+            Some(ViolationKind::UnsortedLongFlags)
+        );
+        assert_eq!(
+            ViolationKind::from_name("unsorted-long-flags"),
+            Some(ViolationKind::UnsortedLongFlags)
+        );
+        assert_eq!(ViolationKind::from_name("CS9999"), None);
+    }
+}